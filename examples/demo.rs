@@ -43,8 +43,12 @@ fn fetch_exec_info(
     let mut process_cpu_usage = 0f64;
     let mut process_vm_size = 0u64;
     let mut process_vm_rss = 0u64;
-    for pid in pids {
-        let pid_cpu_usage = measurement.cpu_load_by_pid(pid as u32)?;
+    // start every pid measurement up front so they share a single delay
+    let pid_cpu_usages = pids
+        .iter()
+        .map(|pid| measurement.cpu_load_by_pid(*pid as u32).map(|m| (*pid, m)))
+        .collect::<Result<Vec<_>, _>>()?;
+    for (pid, pid_cpu_usage) in pid_cpu_usages {
         match pid_cpu_usage.done() {
             Ok(usage) => {
                 process_cpu_usage += usage;
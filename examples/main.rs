@@ -6,28 +6,103 @@ fn main() {
     let cur = std::process::id();
     let measuare = PlatformMeasurement::new();
 
+    let cpu_snapshot = measuare.cpu_time().unwrap();
     let cpu_load = measuare.cpu_load().unwrap();
     println!("{:?}", cpu_load.done());
 
     let cpu_load_aggregate = measuare.cpu_load_aggregate().unwrap();
     println!("agrregate: {:?}\n", cpu_load_aggregate.done());
+    let load_average = measuare.load_average().unwrap();
+    println!("Load average: {:?}\n", load_average);
+    match measuare.pressure() {
+        Ok(pressure) => println!("Pressure: {:?}\n", pressure),
+        Err(e) => println!("Pressure not available: {}\n", e),
+    }
+
     let mem = measuare.memory().unwrap();
     let swap = measuare.swap().unwrap();
-    println!("total: {}, free: {}\n", mem.total, mem.free);
+    println!(
+        "total: {}, free: {}, available: {}, used: {}\n",
+        mem.total, mem.free, mem.available, mem.used
+    );
     println!("total: {}, free: {}\n", swap.total, swap.free);
+    for device in measuare.swap_devices().unwrap() {
+        println!("Swap device: {:?}\n", device);
+    }
+
+    let vmstat_rates = measuare.vmstat_rates().unwrap();
+    println!("VmStat rates: {:?}\n", vmstat_rates.done());
+
+    let hugepages = measuare.hugepages().unwrap();
+    println!("Hugepages: {:?}\n", hugepages);
+
+    if let Ok(slab) = measuare.slab_info() {
+        println!("Slab total: {}", slab.total());
+        for cache in slab.top_n(5) {
+            println!("  {}: {}", cache.name, cache.size);
+        }
+        println!();
+    }
+
+    match measuare.numa_nodes() {
+        Ok(nodes) => {
+            for node in nodes {
+                println!(
+                    "NUMA node {}: total: {}, free: {}, cpus: {:?}\n",
+                    node.id, node.total, node.free, node.cpus
+                );
+            }
+        }
+        Err(e) => println!("NUMA nodes not available: {}\n", e),
+    }
 
     let networks = measuare.networks().unwrap();
     for (name, network) in networks {
         println!("Network: {}", name);
         for addr in network.addrs {
-            println!("  Addr: {:?}, Netmask: {:?}", addr.addr, addr.netmask)
+            println!(
+                "  Addr: {:?}/{:?}, Netmask: {:?}",
+                addr.addr, addr.prefix_len, addr.netmask
+            )
         }
+        println!("  Flags: {:?}", network.flags);
         let stat = measuare.network_stats(&name).unwrap();
         println!("  Stats: {:?}", stat);
     }
 
+    for (name, link) in measuare.network_links().unwrap() {
+        println!("Link {}: {:?}", name, link);
+    }
+
+    let network_rates = measuare.network_rates().unwrap();
+    println!("Network rates: {:?}\n", network_rates.done());
+
+    let protocol_rates = measuare.protocol_rates().unwrap();
+    println!("Protocol rates: {:?}\n", protocol_rates.done());
+
     let socket_stats = measuare.socket_stats().unwrap();
     println!("Socket Stats: {:?}", socket_stats);
+    for socket in measuare.sockets().unwrap() {
+        println!(
+            "  {:?} {} -> {} {:?} rtt: {:?}",
+            socket.protocol,
+            socket.local,
+            socket.remote,
+            socket.state,
+            socket.tcp_info.map(|info| info.rtt)
+        );
+    }
+
+    let unix_sockets = measuare.unix_sockets().unwrap();
+    println!("Unix sockets: {}", unix_sockets.len());
+
+    for port in measuare.listening_ports().unwrap() {
+        println!(
+            "Listening: {:?} {} {:?}",
+            port.socket.protocol, port.socket.local, port.owners
+        );
+    }
+    println!("Own sockets: {:?}\n", measuare.sockets_by_pid(cur));
 
     let mounts = measuare.mounts().unwrap();
     for fs in mounts {
@@ -42,6 +117,18 @@ fn main() {
     let root = measuare.mount_at("/").unwrap();
     println!("Root mount: {:?}\n", root);
 
+    let block_devices = measuare.block_device_stats().unwrap();
+    for (name, stats) in block_devices {
+        println!("Block device: {}", name);
+        println!("  Stats: {:?}\n", stats);
+    }
+
+    let block_device_load = measuare.block_device_load().unwrap();
+    for (name, load) in block_device_load.done().unwrap() {
+        println!("Block device load: {}", name);
+        println!("  Load: {:?}\n", load);
+    }
+
     let boot_time = measuare.boot_time().unwrap();
     println!("Boot time: {}\n", boot_time);
 
@@ -51,5 +138,12 @@ fn main() {
     let pid = measuare.process_pid("relay-agent").unwrap();
     println!("Process pid for relay_agent: {:?}\n", pid);
 
+    let cpu_delta = measuare.cpu_time().unwrap() - &cpu_snapshot;
+    println!(
+        "CPU load over {:?}: {:?}\n",
+        cpu_delta.elapsed,
+        cpu_delta.loads()
+    );
+
     println!("Elapsed: {:?}\n", start.elapsed());
 }
@@ -0,0 +1,259 @@
+use std::{
+    collections::BTreeMap, io, path::PathBuf, sync::Arc, time::Duration,
+};
+
+use time::OffsetDateTime;
+
+use crate::{
+    disk::{BlockDeviceLoad, BlockDeviceStats, FileSystem},
+    network::{
+        ListeningPort, Network, NetworkLink, NetworkRates, NetworkStats,
+        ProtocolCounters, ProtocolRates, Socket, SocketStats, UnixSocket,
+    },
+    numa::NumaNode,
+    process::ProcessInfo,
+    CpuSnapshot, DelayedMeasurement, HugePages, LoadAverage, Measurement,
+    SlabInfo, SwapDevice, SystemCpuLoad, SystemMemory, SystemPressure,
+    SystemSwap, VmStat, VmStatRates,
+};
+
+/// Async facade over a [`Measurement`] for use on a tokio runtime.
+///
+/// Blocking `/proc` reads run on `spawn_blocking` and delayed measurements
+/// wait with `tokio::time::sleep`, so the executor is never stalled.
+pub struct AsyncMeasurement<M> {
+    inner: Arc<M>,
+}
+
+impl<M> Clone for AsyncMeasurement<M> {
+    fn clone(&self) -> Self {
+        AsyncMeasurement {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<M> From<M> for AsyncMeasurement<M> {
+    fn from(measurement: M) -> Self {
+        AsyncMeasurement {
+            inner: Arc::new(measurement),
+        }
+    }
+}
+
+impl<M: Measurement + Send + Sync + 'static> AsyncMeasurement<M> {
+    pub fn new() -> Self {
+        M::new().into()
+    }
+
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Runs `f` against the inner measurement on the blocking thread pool.
+    pub async fn blocking<T, F>(&self, f: F) -> io::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&M) -> io::Result<T> + Send + 'static,
+    {
+        let inner = self.inner.clone();
+        tokio::task::spawn_blocking(move || f(&inner))
+            .await
+            .map_err(io::Error::other)?
+    }
+
+    /// Starts the delayed measurement returned by `f` and waits for it
+    /// without blocking the executor.
+    pub async fn delayed<T, F>(&self, f: F) -> io::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&M) -> io::Result<DelayedMeasurement<T>> + Send + 'static,
+    {
+        self.blocking(f).await?.done_async().await
+    }
+
+    pub async fn cpu_time(&self) -> io::Result<CpuSnapshot> {
+        self.blocking(|m| m.cpu_time()).await
+    }
+
+    pub async fn cpu_load(&self) -> io::Result<Vec<SystemCpuLoad>> {
+        self.delayed(|m| m.cpu_load()).await
+    }
+
+    pub async fn cpu_load_aggregate(&self) -> io::Result<SystemCpuLoad> {
+        self.delayed(|m| m.cpu_load_aggregate()).await
+    }
+
+    pub async fn cpu_load_by_pid(&self, pid: u32) -> io::Result<f64> {
+        self.delayed(move |m| m.cpu_load_by_pid(pid)).await
+    }
+
+    pub async fn load_average(&self) -> io::Result<LoadAverage> {
+        self.blocking(|m| m.load_average()).await
+    }
+
+    pub async fn pressure(&self) -> io::Result<SystemPressure> {
+        self.blocking(|m| m.pressure()).await
+    }
+
+    pub async fn cgroup_pressure<P: Into<PathBuf>>(
+        &self,
+        cgroup: P,
+    ) -> io::Result<SystemPressure> {
+        let cgroup = cgroup.into();
+        self.blocking(move |m| m.cgroup_pressure(cgroup)).await
+    }
+
+    pub async fn memory(&self) -> io::Result<SystemMemory> {
+        self.blocking(|m| m.memory()).await
+    }
+
+    pub async fn memory_by_pid(&self, pid: u32) -> io::Result<(u64, u64)> {
+        self.blocking(move |m| m.memory_by_pid(pid)).await
+    }
+
+    pub async fn vmstat(&self) -> io::Result<VmStat> {
+        self.blocking(|m| m.vmstat()).await
+    }
+
+    pub async fn vmstat_rates(&self) -> io::Result<VmStatRates> {
+        self.delayed(|m| m.vmstat_rates()).await
+    }
+
+    pub async fn swap(&self) -> io::Result<SystemSwap> {
+        self.blocking(|m| m.swap()).await
+    }
+
+    pub async fn swap_devices(&self) -> io::Result<Vec<SwapDevice>> {
+        self.blocking(|m| m.swap_devices()).await
+    }
+
+    pub async fn hugepages(&self) -> io::Result<HugePages> {
+        self.blocking(|m| m.hugepages()).await
+    }
+
+    pub async fn slab_info(&self) -> io::Result<SlabInfo> {
+        self.blocking(|m| m.slab_info()).await
+    }
+
+    pub async fn numa_nodes(&self) -> io::Result<Vec<NumaNode>> {
+        self.blocking(|m| m.numa_nodes()).await
+    }
+
+    pub async fn mounts(&self) -> io::Result<Vec<FileSystem>> {
+        self.blocking(|m| m.mounts()).await
+    }
+
+    pub async fn mount_at<P: Into<PathBuf>>(
+        &self,
+        path: P,
+    ) -> io::Result<FileSystem> {
+        let path = path.into();
+        self.blocking(move |m| m.mount_at(path)).await
+    }
+
+    pub async fn block_device_stats(
+        &self,
+    ) -> io::Result<BTreeMap<String, BlockDeviceStats>> {
+        self.blocking(|m| m.block_device_stats()).await
+    }
+
+    pub async fn block_device_load(
+        &self,
+    ) -> io::Result<BTreeMap<String, BlockDeviceLoad>> {
+        self.delayed(|m| m.block_device_load()).await
+    }
+
+    pub async fn networks(&self) -> io::Result<BTreeMap<String, Network>> {
+        self.blocking(|m| m.networks()).await
+    }
+
+    pub async fn network_links(
+        &self,
+    ) -> io::Result<BTreeMap<String, NetworkLink>> {
+        self.blocking(|m| m.network_links()).await
+    }
+
+    pub async fn network_stats(
+        &self,
+        interface: &str,
+    ) -> io::Result<NetworkStats> {
+        let interface = interface.to_string();
+        self.blocking(move |m| m.network_stats(&interface)).await
+    }
+
+    pub async fn all_network_stats(
+        &self,
+    ) -> io::Result<BTreeMap<String, NetworkStats>> {
+        self.blocking(|m| m.all_network_stats()).await
+    }
+
+    pub async fn network_rates(
+        &self,
+    ) -> io::Result<BTreeMap<String, NetworkRates>> {
+        self.delayed(|m| m.network_rates()).await
+    }
+
+    pub async fn socket_stats(&self) -> io::Result<SocketStats> {
+        self.blocking(|m| m.socket_stats()).await
+    }
+
+    pub async fn protocol_counters(&self) -> io::Result<ProtocolCounters> {
+        self.blocking(|m| m.protocol_counters()).await
+    }
+
+    pub async fn protocol_rates(&self) -> io::Result<ProtocolRates> {
+        self.delayed(|m| m.protocol_rates()).await
+    }
+
+    pub async fn sockets(&self) -> io::Result<Vec<Socket>> {
+        self.blocking(|m| m.sockets()).await
+    }
+
+    pub async fn unix_sockets(&self) -> io::Result<Vec<UnixSocket>> {
+        self.blocking(|m| m.unix_sockets()).await
+    }
+
+    pub async fn listening_ports(&self) -> io::Result<Vec<ListeningPort>> {
+        self.blocking(|m| m.listening_ports()).await
+    }
+
+    pub async fn sockets_by_pid(&self, pid: u32) -> io::Result<Vec<Socket>> {
+        self.blocking(move |m| m.sockets_by_pid(pid)).await
+    }
+
+    pub async fn boot_time(&self) -> io::Result<OffsetDateTime> {
+        self.blocking(|m| m.boot_time()).await
+    }
+
+    pub async fn process_uptime(&self, pid: u32) -> io::Result<Duration> {
+        self.blocking(move |m| m.process_uptime(pid)).await
+    }
+
+    pub async fn process_pid(&self, cmd: &str) -> io::Result<Vec<usize>> {
+        let cmd = cmd.to_string();
+        self.blocking(move |m| m.process_pid(&cmd)).await
+    }
+
+    pub async fn process_status(&self, pid: u32) -> io::Result<ProcessInfo> {
+        self.blocking(move |m| m.process_status(pid)).await
+    }
+}
+
+impl<M: Measurement + Send + Sync + 'static> Default for AsyncMeasurement<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+fn test_async_delayed_measurement() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+    let elapsed = runtime
+        .block_on(DelayedMeasurement::new(Box::new(Ok), Some(1)).done_async())
+        .unwrap();
+    assert!(elapsed >= Duration::from_secs(1));
+}
@@ -1,25 +1,40 @@
-use std::{io, thread::sleep, time::Duration};
+use std::{
+    io,
+    thread::sleep,
+    time::{Duration, Instant},
+};
 
 pub mod cpu;
 pub mod disk;
 pub mod memory;
 pub mod network;
+pub mod numa;
+pub mod pressure;
 pub mod process;
 
 use bytesize::ByteSize;
 pub use cpu::*;
 pub use memory::*;
+pub use pressure::*;
 
 #[inline(always)]
 pub fn saturating_sub_bytes(l: ByteSize, r: ByteSize) -> ByteSize {
     ByteSize::b(l.as_u64().saturating_sub(r.as_u64()))
 }
 
-type DelayedCallback<T> = Box<dyn Fn() -> io::Result<T> + Send>;
+type DelayedCallback<T> = Box<dyn Fn(Duration) -> io::Result<T> + Send>;
 
+/// A measurement that compares a start snapshot against a later one.
+///
+/// The start snapshot is taken when the measurement is created. The result
+/// can be computed at any time with [`DelayedMeasurement::measure`], which
+/// hands the callback the real time elapsed since the start, or with
+/// [`DelayedMeasurement::done`], which first sleeps until the configured
+/// duration has passed.
 pub struct DelayedMeasurement<T> {
     res: DelayedCallback<T>,
     duration: Duration,
+    started: Instant,
 }
 
 impl<T> DelayedMeasurement<T> {
@@ -28,16 +43,88 @@ impl<T> DelayedMeasurement<T> {
         f: DelayedCallback<T>,
         duration_sec: Option<u64>,
     ) -> DelayedMeasurement<T> {
-        let sec = duration_sec.map_or_else(|| 1, |s| s);
+        let sec = duration_sec.unwrap_or(1);
         DelayedMeasurement {
             res: f,
             duration: Duration::from_secs(sec),
+            started: Instant::now(),
         }
     }
 
+    /// Time elapsed since the start snapshot was taken.
+    #[inline(always)]
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Whether the configured duration has passed, i.e. `done()` would not
+    /// block.
+    #[inline(always)]
+    pub fn is_ready(&self) -> bool {
+        self.elapsed() >= self.duration
+    }
+
+    /// Computes the result right away over the time elapsed so far, without
+    /// sleeping.
+    #[inline(always)]
+    pub fn measure(&self) -> io::Result<T> {
+        (self.res)(self.elapsed())
+    }
+
+    /// Sleeps for whatever is left of the configured duration, then computes
+    /// the result.
     #[inline(always)]
     pub fn done(&self) -> io::Result<T> {
-        sleep(self.duration);
-        (self.res)()
+        sleep(self.duration.saturating_sub(self.elapsed()));
+        self.measure()
+    }
+
+    /// Like [`DelayedMeasurement::map`] for transformations that can fail.
+    pub fn and_then<U, F>(self, f: F) -> DelayedMeasurement<U>
+    where
+        T: 'static,
+        F: Fn(T) -> io::Result<U> + Send + 'static,
+    {
+        let res = self.res;
+        DelayedMeasurement {
+            res: Box::new(move |elapsed| res(elapsed).and_then(&f)),
+            duration: self.duration,
+            started: self.started,
+        }
+    }
+
+    /// Transforms the result while keeping the start snapshot and duration.
+    pub fn map<U, F>(self, f: F) -> DelayedMeasurement<U>
+    where
+        T: 'static,
+        F: Fn(T) -> U + Send + 'static,
+    {
+        let res = self.res;
+        DelayedMeasurement {
+            res: Box::new(move |elapsed| res(elapsed).map(&f)),
+            duration: self.duration,
+            started: self.started,
+        }
     }
 }
+
+#[cfg(feature = "tokio")]
+impl<T: Send + 'static> DelayedMeasurement<T> {
+    /// Async counterpart of [`DelayedMeasurement::done`]: waits with
+    /// `tokio::time::sleep` and computes the result on the blocking pool.
+    pub async fn done_async(self) -> io::Result<T> {
+        tokio::time::sleep(self.duration.saturating_sub(self.elapsed())).await;
+        tokio::task::spawn_blocking(move || self.measure())
+            .await
+            .map_err(io::Error::other)?
+    }
+}
+
+#[test]
+fn test_delayed_measurement_measure() {
+    let measurement = DelayedMeasurement::new(Box::new(Ok), Some(60))
+        .map(|elapsed| elapsed.as_secs());
+    assert!(!measurement.is_ready());
+    // measure() must not wait for the 60 seconds to pass
+    assert_eq!(measurement.measure().unwrap(), 0);
+}
@@ -1,6 +1,9 @@
-use std::ops::Sub;
+use std::{
+    ops::Sub,
+    time::{Duration, Instant},
+};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct SystemCpuTime {
     pub user: usize,
     pub nice: usize,
@@ -8,9 +11,10 @@ pub struct SystemCpuTime {
     pub interrupt: usize,
     pub idle: usize,
     pub other: usize,
+    pub platform: PlatformCpuTime,
 }
 
-impl<'a> Sub<&'a SystemCpuTime> for SystemCpuTime {
+impl Sub<&SystemCpuTime> for SystemCpuTime {
     type Output = SystemCpuTime;
 
     #[inline(always)]
@@ -23,18 +27,19 @@ impl<'a> Sub<&'a SystemCpuTime> for SystemCpuTime {
             interrupt: self.interrupt.saturating_sub(rhs.interrupt),
             idle: self.idle.saturating_sub(rhs.idle),
             other: self.other.saturating_sub(rhs.other),
+            platform: self.platform - &rhs.platform,
         }
     }
 }
 
-impl Into<SystemCpuLoad> for SystemCpuTime {
-    fn into(self) -> SystemCpuLoad {
-        let total = self.user
-            + self.nice
-            + self.system
-            + self.interrupt
-            + self.idle
-            + self.other;
+impl From<SystemCpuTime> for SystemCpuLoad {
+    fn from(time: SystemCpuTime) -> SystemCpuLoad {
+        let total = time.user
+            + time.nice
+            + time.system
+            + time.interrupt
+            + time.idle
+            + time.other;
 
         if total == 0 {
             SystemCpuLoad {
@@ -47,19 +52,133 @@ impl Into<SystemCpuLoad> for SystemCpuTime {
             }
         } else {
             SystemCpuLoad {
-                user: self.user as f32 / total as f32,
-                nice: self.nice as f32 / total as f32,
-                system: self.system as f32 / total as f32,
-                interrupt: self.interrupt as f32 / total as f32,
-                idle: self.idle as f32 / total as f32,
-                platform: PlatformCpuLoad::from(
-                    self.other as f32 / total as f32,
-                ),
+                user: time.user as f32 / total as f32,
+                nice: time.nice as f32 / total as f32,
+                system: time.system as f32 / total as f32,
+                interrupt: time.interrupt as f32 / total as f32,
+                idle: time.idle as f32 / total as f32,
+                platform: PlatformCpuLoad::new(&time.platform, total as f32),
             }
         }
     }
 }
 
+/// System-wide and per-core CPU times captured at a point in time.
+///
+/// Subtracting an older snapshot from a newer one yields a [`CpuDelta`],
+/// so a long-running collector can keep the previous snapshot around and
+/// compute the load on every scrape without sleeping.
+#[derive(Debug, Clone)]
+pub struct CpuSnapshot {
+    pub taken_at: Instant,
+    /// Times summed over all cores
+    pub total: SystemCpuTime,
+    pub times: Vec<SystemCpuTime>,
+}
+
+impl CpuSnapshot {
+    pub fn new(total: SystemCpuTime, times: Vec<SystemCpuTime>) -> Self {
+        CpuSnapshot {
+            taken_at: Instant::now(),
+            total,
+            times,
+        }
+    }
+}
+
+impl Sub<&CpuSnapshot> for &CpuSnapshot {
+    type Output = CpuDelta;
+
+    fn sub(self, rhs: &CpuSnapshot) -> CpuDelta {
+        CpuDelta {
+            elapsed: self.taken_at.saturating_duration_since(rhs.taken_at),
+            total: self.total - &rhs.total,
+            times: self
+                .times
+                .iter()
+                .zip(rhs.times.iter())
+                .map(|(now, prev)| *now - prev)
+                .collect(),
+        }
+    }
+}
+
+impl Sub<&CpuSnapshot> for CpuSnapshot {
+    type Output = CpuDelta;
+
+    #[inline(always)]
+    fn sub(self, rhs: &CpuSnapshot) -> CpuDelta {
+        &self - rhs
+    }
+}
+
+/// CPU times spent between two [`CpuSnapshot`]s.
+#[derive(Debug, Clone)]
+pub struct CpuDelta {
+    pub elapsed: Duration,
+    pub total: SystemCpuTime,
+    pub times: Vec<SystemCpuTime>,
+}
+
+impl CpuDelta {
+    /// Load of the whole system
+    pub fn load(&self) -> SystemCpuLoad {
+        self.total.into()
+    }
+
+    /// Load of each core
+    pub fn loads(&self) -> Vec<SystemCpuLoad> {
+        self.times.iter().map(|time| (*time).into()).collect()
+    }
+}
+
+impl From<CpuDelta> for SystemCpuLoad {
+    fn from(delta: CpuDelta) -> Self {
+        delta.load()
+    }
+}
+
+impl From<CpuDelta> for Vec<SystemCpuLoad> {
+    fn from(delta: CpuDelta) -> Self {
+        delta.loads()
+    }
+}
+
+#[test]
+fn test_cpu_snapshot_delta() {
+    let time = |user, idle| SystemCpuTime {
+        user,
+        nice: 0,
+        system: 0,
+        interrupt: 0,
+        idle,
+        other: 0,
+        platform: PlatformCpuTime::default(),
+    };
+    let prev = CpuSnapshot {
+        taken_at: Instant::now(),
+        total: time(300, 300),
+        times: vec![time(100, 100), time(200, 200)],
+    };
+    let now = CpuSnapshot {
+        taken_at: prev.taken_at + Duration::from_secs(2),
+        total: time(350, 450),
+        times: vec![time(150, 150), time(200, 300)],
+    };
+
+    let delta = &now - &prev;
+    assert_eq!(delta.elapsed, Duration::from_secs(2));
+    let load = delta.load();
+    assert_eq!(load.user, 0.25);
+    assert_eq!(load.idle, 0.75);
+    let loads: Vec<SystemCpuLoad> = delta.into();
+    assert_eq!(loads.len(), 2);
+    assert_eq!(loads[0].user, 0.5);
+    assert_eq!(loads[0].idle, 0.5);
+    assert_eq!(loads[1].user, 0.0);
+    assert_eq!(loads[1].idle, 1.0);
+}
+
 #[derive(Debug, Clone)]
 pub struct SystemCpuLoad {
     pub user: f32,
@@ -71,6 +190,10 @@ pub struct SystemCpuLoad {
 }
 
 impl SystemCpuLoad {
+    /// Averages `self` and `rhs` with equal weight. Folding a list with it
+    /// halves the weight of every earlier element, use
+    /// [`SystemCpuLoad::mean`] to combine more than two loads.
+    #[deprecated(note = "use SystemCpuLoad::mean or weighted_mean instead")]
     #[inline(always)]
     pub fn avg_add(self, rhs: &Self) -> Self {
         SystemCpuLoad {
@@ -82,6 +205,109 @@ impl SystemCpuLoad {
             platform: self.platform.avg_add(&rhs.platform),
         }
     }
+
+    /// Arithmetic mean of `loads`, `None` if there are none.
+    pub fn mean<'a, I>(loads: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = &'a SystemCpuLoad>,
+    {
+        Self::weighted_mean(loads.into_iter().map(|load| (load, 1.0)))
+    }
+
+    /// Mean of `loads` where each load counts proportionally to its weight,
+    /// e.g. the number of ticks it was computed over. `None` if there are
+    /// no loads or the weights sum up to zero.
+    pub fn weighted_mean<'a, I>(loads: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = (&'a SystemCpuLoad, f32)>,
+    {
+        let mut total_weight = 0.0;
+        let sum = loads.into_iter().fold(
+            SystemCpuLoad {
+                user: 0.0,
+                nice: 0.0,
+                system: 0.0,
+                interrupt: 0.0,
+                idle: 0.0,
+                platform: PlatformCpuLoad::default(),
+            },
+            |acc, (load, weight)| {
+                total_weight += weight;
+                SystemCpuLoad {
+                    user: acc.user + load.user * weight,
+                    nice: acc.nice + load.nice * weight,
+                    system: acc.system + load.system * weight,
+                    interrupt: acc.interrupt + load.interrupt * weight,
+                    idle: acc.idle + load.idle * weight,
+                    platform: acc.platform.scaled_add(&load.platform, weight),
+                }
+            },
+        );
+
+        if total_weight == 0.0 {
+            return None;
+        }
+        Some(SystemCpuLoad {
+            user: sum.user / total_weight,
+            nice: sum.nice / total_weight,
+            system: sum.system / total_weight,
+            interrupt: sum.interrupt / total_weight,
+            idle: sum.idle / total_weight,
+            platform: sum.platform.scale(1.0 / total_weight),
+        })
+    }
+}
+
+#[test]
+fn test_system_cpu_load_mean() {
+    let load = |user: f32| SystemCpuLoad {
+        user,
+        nice: 0.0,
+        system: 0.0,
+        interrupt: 0.0,
+        idle: 1.0 - user,
+        platform: PlatformCpuLoad::default(),
+    };
+    let loads = [load(0.2), load(0.4), load(0.6), load(1.0)];
+
+    let mean = SystemCpuLoad::mean(&loads).unwrap();
+    assert!((mean.user - 0.55).abs() < f32::EPSILON);
+    assert!((mean.idle - 0.45).abs() < f32::EPSILON);
+
+    let weighted =
+        SystemCpuLoad::weighted_mean(loads.iter().zip([1.0, 1.0, 1.0, 5.0]))
+            .unwrap();
+    assert!((weighted.user - 0.775).abs() < f32::EPSILON);
+
+    assert!(SystemCpuLoad::mean(&[]).is_none());
+}
+
+/// System load averages and run queue, as reported by `/proc/loadavg`
+#[derive(Debug, Clone, Default)]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+    /// Currently runnable scheduling entities (`None` from `getloadavg`)
+    pub runnable: Option<usize>,
+    /// Existing scheduling entities (`None` from `getloadavg`)
+    pub total: Option<usize>,
+    /// Most recently created pid (`None` from `getloadavg`)
+    pub last_pid: Option<u32>,
+}
+
+#[cfg(not(target_os = "linux"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlatformCpuTime {}
+
+#[cfg(not(target_os = "linux"))]
+impl Sub<&PlatformCpuTime> for PlatformCpuTime {
+    type Output = PlatformCpuTime;
+
+    #[inline(always)]
+    fn sub(self, _rhs: &PlatformCpuTime) -> PlatformCpuTime {
+        self
+    }
 }
 
 #[cfg(not(target_os = "linux"))]
@@ -97,15 +323,66 @@ impl PlatformCpuLoad {
 
     #[cfg(not(target_os = "linux"))]
     #[inline(always)]
-    pub fn from(_input: f32) -> Self {
+    pub fn new(_time: &PlatformCpuTime, _total: f32) -> Self {
         PlatformCpuLoad {}
     }
+
+    #[cfg(not(target_os = "linux"))]
+    #[inline(always)]
+    pub fn scaled_add(self, _rhs: &Self, _weight: f32) -> Self {
+        self
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    #[inline(always)]
+    pub fn scale(self, _factor: f32) -> Self {
+        self
+    }
+}
+
+/// The `/proc/stat` columns beyond user, nice, system and idle.
+///
+/// `irq + softirq` is folded into [`SystemCpuTime::interrupt`] and
+/// `iowait + steal` into [`SystemCpuTime::other`]. `guest` and `guest_nice`
+/// are already accounted in `user` and `nice` by the kernel, so they are not
+/// part of the total.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlatformCpuTime {
+    pub iowait: usize,
+    pub irq: usize,
+    pub softirq: usize,
+    pub steal: usize,
+    pub guest: usize,
+    pub guest_nice: usize,
+}
+
+#[cfg(target_os = "linux")]
+impl Sub<&PlatformCpuTime> for PlatformCpuTime {
+    type Output = PlatformCpuTime;
+
+    #[inline(always)]
+    fn sub(self, rhs: &PlatformCpuTime) -> PlatformCpuTime {
+        PlatformCpuTime {
+            iowait: self.iowait.saturating_sub(rhs.iowait),
+            irq: self.irq.saturating_sub(rhs.irq),
+            softirq: self.softirq.saturating_sub(rhs.softirq),
+            steal: self.steal.saturating_sub(rhs.steal),
+            guest: self.guest.saturating_sub(rhs.guest),
+            guest_nice: self.guest_nice.saturating_sub(rhs.guest_nice),
+        }
+    }
 }
 
 #[cfg(target_os = "linux")]
 #[derive(Debug, Clone, Default)]
 pub struct PlatformCpuLoad {
     pub iowait: f32,
+    pub irq: f32,
+    pub softirq: f32,
+    pub steal: f32,
+    pub guest: f32,
+    pub guest_nice: f32,
 }
 
 impl PlatformCpuLoad {
@@ -114,12 +391,80 @@ impl PlatformCpuLoad {
     pub fn avg_add(self, rhs: &Self) -> Self {
         PlatformCpuLoad {
             iowait: (self.iowait + rhs.iowait) / 2.0,
+            irq: (self.irq + rhs.irq) / 2.0,
+            softirq: (self.softirq + rhs.softirq) / 2.0,
+            steal: (self.steal + rhs.steal) / 2.0,
+            guest: (self.guest + rhs.guest) / 2.0,
+            guest_nice: (self.guest_nice + rhs.guest_nice) / 2.0,
         }
     }
 
     #[cfg(target_os = "linux")]
     #[inline(always)]
-    pub fn from(input: f32) -> Self {
-        PlatformCpuLoad { iowait: input }
+    pub fn new(time: &PlatformCpuTime, total: f32) -> Self {
+        PlatformCpuLoad {
+            iowait: time.iowait as f32 / total,
+            irq: time.irq as f32 / total,
+            softirq: time.softirq as f32 / total,
+            steal: time.steal as f32 / total,
+            guest: time.guest as f32 / total,
+            guest_nice: time.guest_nice as f32 / total,
+        }
     }
+
+    /// Adds `rhs` multiplied by `weight`
+    #[cfg(target_os = "linux")]
+    #[inline(always)]
+    pub fn scaled_add(self, rhs: &Self, weight: f32) -> Self {
+        PlatformCpuLoad {
+            iowait: self.iowait + rhs.iowait * weight,
+            irq: self.irq + rhs.irq * weight,
+            softirq: self.softirq + rhs.softirq * weight,
+            steal: self.steal + rhs.steal * weight,
+            guest: self.guest + rhs.guest * weight,
+            guest_nice: self.guest_nice + rhs.guest_nice * weight,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[inline(always)]
+    pub fn scale(self, factor: f32) -> Self {
+        PlatformCpuLoad {
+            iowait: self.iowait * factor,
+            irq: self.irq * factor,
+            softirq: self.softirq * factor,
+            steal: self.steal * factor,
+            guest: self.guest * factor,
+            guest_nice: self.guest_nice * factor,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_platform_cpu_load() {
+    let time = SystemCpuTime {
+        user: 40,
+        nice: 0,
+        system: 10,
+        interrupt: 10,
+        idle: 20,
+        other: 20,
+        platform: PlatformCpuTime {
+            iowait: 5,
+            irq: 4,
+            softirq: 6,
+            steal: 15,
+            guest: 30,
+            guest_nice: 0,
+        },
+    };
+    let load: SystemCpuLoad = time.into();
+    assert_eq!(load.user, 0.4);
+    assert_eq!(load.interrupt, 0.1);
+    assert_eq!(load.platform.iowait, 0.05);
+    assert_eq!(load.platform.irq, 0.04);
+    assert_eq!(load.platform.softirq, 0.06);
+    assert_eq!(load.platform.steal, 0.15);
+    assert_eq!(load.platform.guest, 0.3);
 }
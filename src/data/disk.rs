@@ -1,5 +1,10 @@
+use std::time::Duration;
+
 use bytesize::ByteSize;
 
+/// `/proc/diskstats` always counts in 512-byte sectors
+const SECTOR_SIZE: f64 = 512.0;
+
 #[derive(Debug, Clone)]
 pub struct FileSystem {
     /// Used file nodes in filesystem
@@ -21,7 +26,10 @@ pub struct FileSystem {
     pub fs_mounted_on: String,
 }
 
+#[derive(Debug, Clone, Default)]
 pub struct BlockDeviceStats {
+    pub major: u32,
+    pub minor: u32,
     pub name: String,
     pub read_ios: usize,
     pub read_merges: usize,
@@ -34,4 +42,130 @@ pub struct BlockDeviceStats {
     pub in_flight: usize,
     pub io_ticks: usize,
     pub time_in_queue: usize,
+    /// Discard counters, reported since Linux 4.18 (0 on older kernels)
+    pub discard_ios: usize,
+    pub discard_merges: usize,
+    pub discard_sectors: usize,
+    pub discard_ticks: usize,
+    /// Flush counters, reported since Linux 5.5 (0 on older kernels)
+    pub flush_ios: usize,
+    pub flush_ticks: usize,
+}
+
+impl BlockDeviceStats {
+    /// Computes `iostat -x` style rates from the counters accumulated
+    /// between `prev` and `self` over `elapsed`.
+    pub fn load_since(
+        &self,
+        prev: &BlockDeviceStats,
+        elapsed: Duration,
+    ) -> BlockDeviceLoad {
+        let secs = elapsed.as_secs_f64();
+        if secs == 0.0 {
+            return BlockDeviceLoad {
+                name: self.name.clone(),
+                ..Default::default()
+            };
+        }
+
+        let delta =
+            |now: usize, before: usize| now.saturating_sub(before) as f64;
+        let await_ms = |ticks: f64, ios: f64| {
+            if ios == 0.0 {
+                0.0
+            } else {
+                ticks / ios
+            }
+        };
+
+        let read_ios = delta(self.read_ios, prev.read_ios);
+        let write_ios = delta(self.write_ios, prev.write_ios);
+        let read_ticks = delta(self.read_ticks, prev.read_ticks);
+        let write_ticks = delta(self.write_ticks, prev.write_ticks);
+        let elapsed_ms = secs * 1000.0;
+
+        BlockDeviceLoad {
+            name: self.name.clone(),
+            read_iops: read_ios / secs,
+            write_iops: write_ios / secs,
+            read_bytes_per_sec: delta(self.read_sectors, prev.read_sectors)
+                * SECTOR_SIZE
+                / secs,
+            write_bytes_per_sec: delta(self.write_sectors, prev.write_sectors)
+                * SECTOR_SIZE
+                / secs,
+            read_await: await_ms(read_ticks, read_ios),
+            write_await: await_ms(write_ticks, write_ios),
+            await_ms: await_ms(read_ticks + write_ticks, read_ios + write_ios),
+            queue_depth: delta(self.time_in_queue, prev.time_in_queue)
+                / elapsed_ms,
+            utilization: (delta(self.io_ticks, prev.io_ticks) / elapsed_ms
+                * 100.0)
+                .min(100.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BlockDeviceLoad {
+    pub name: String,
+    /// Completed reads per second (`r/s`)
+    pub read_iops: f64,
+    /// Completed writes per second (`w/s`)
+    pub write_iops: f64,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    /// Average time in milliseconds a read took to be served (`r_await`)
+    pub read_await: f64,
+    /// Average time in milliseconds a write took to be served (`w_await`)
+    pub write_await: f64,
+    /// Average time in milliseconds for all reads and writes (`await`)
+    pub await_ms: f64,
+    /// Average number of requests queued on the device (`aqu-sz`)
+    pub queue_depth: f64,
+    /// Percentage of time the device was busy (`%util`)
+    pub utilization: f64,
+}
+
+#[test]
+fn test_block_device_load() {
+    let prev = BlockDeviceStats {
+        name: "sda".to_string(),
+        read_ios: 100,
+        read_sectors: 1000,
+        read_ticks: 50,
+        write_ios: 10,
+        write_sectors: 80,
+        write_ticks: 40,
+        io_ticks: 1000,
+        time_in_queue: 2000,
+        ..Default::default()
+    };
+    let now = BlockDeviceStats {
+        read_ios: 300,
+        read_sectors: 5000,
+        read_ticks: 450,
+        write_ios: 60,
+        write_sectors: 1080,
+        write_ticks: 140,
+        io_ticks: 1500,
+        time_in_queue: 3000,
+        ..prev.clone()
+    };
+
+    let load = now.load_since(&prev, Duration::from_secs(2));
+    assert_eq!(load.name, "sda");
+    assert_eq!(load.read_iops, 100.0);
+    assert_eq!(load.write_iops, 25.0);
+    assert_eq!(load.read_bytes_per_sec, 4000.0 * 512.0 / 2.0);
+    assert_eq!(load.write_bytes_per_sec, 1000.0 * 512.0 / 2.0);
+    assert_eq!(load.read_await, 2.0);
+    assert_eq!(load.write_await, 2.0);
+    assert_eq!(load.await_ms, 2.0);
+    assert_eq!(load.queue_depth, 0.5);
+    assert_eq!(load.utilization, 25.0);
+
+    let idle = now.load_since(&now, Duration::from_secs(1));
+    assert_eq!(idle.read_await, 0.0);
+    assert_eq!(idle.utilization, 0.0);
 }
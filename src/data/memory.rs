@@ -1,10 +1,34 @@
+use std::time::Duration;
+
 use bytesize::ByteSize;
 
+/// System memory usage.
+///
+/// On Linux the fields map to `/proc/meminfo` the way `free(1)` reads them:
+/// `free(1)`'s `buff/cache` column is `buffers + cached + slab_reclaimable`,
+/// and `used` is `total - available`, as computed by procps-ng 4. On kernels
+/// without `MemAvailable` (older than 3.14) `available` is estimated as
+/// `free + buffers + cached + slab_reclaimable - shared`.
+/// Without a readable `/proc/meminfo`, the `sysinfo(2)` fallback knows
+/// nothing about the page cache, so `available` is only `free + buffers`,
+/// a lower bound of the real value.
 #[derive(Debug, Clone)]
 pub struct SystemMemory {
     pub total: ByteSize,
+    /// Memory not used for anything (`MemFree`)
     pub free: ByteSize,
+    /// Memory that can be handed to new workloads without swapping
+    /// (`MemAvailable`)
+    pub available: ByteSize,
     pub used: ByteSize,
+    /// Page cache, excluding the swap cache (`Cached`)
+    pub cached: ByteSize,
+    /// Block device buffers (`Buffers`)
+    pub buffers: ByteSize,
+    /// tmpfs and shared memory, part of `cached` (`Shmem`)
+    pub shared: ByteSize,
+    /// Slab allocations that can be reclaimed (`SReclaimable`)
+    pub slab_reclaimable: ByteSize,
     pub platform: PlatformMemory,
 }
 
@@ -21,6 +45,8 @@ pub struct PlatformMemory {
 
 #[cfg(target_os = "linux")]
 pub use std::collections::BTreeMap;
+#[cfg(not(target_os = "linux"))]
+use std::collections::BTreeMap;
 
 #[cfg(target_os = "linux")]
 #[derive(Debug, Clone)]
@@ -44,3 +70,266 @@ pub struct PlatformSwap {
 
 #[cfg(target_os = "linux")]
 pub type PlatformSwap = PlatformMemory;
+
+/// A swap partition or file, as listed in `/proc/swaps`
+#[derive(Debug, Clone)]
+pub struct SwapDevice {
+    /// Path of the device or file, e.g. `/dev/zram0` or `/swapfile`
+    pub name: String,
+    /// `partition` or `file`
+    pub kind: String,
+    pub size: ByteSize,
+    pub used: ByteSize,
+    /// Devices with higher priority are used first
+    pub priority: i32,
+    /// Compression stats, when the device is a zram block device
+    pub zram: Option<ZramStats>,
+}
+
+/// Compression stats of a zram device, from `/sys/block/zram*/mm_stat`
+#[derive(Debug, Clone, Default)]
+pub struct ZramStats {
+    /// Uncompressed size of the data stored on the device
+    pub orig_data_size: ByteSize,
+    /// Compressed size of the data stored on the device
+    pub compr_data_size: ByteSize,
+    /// Memory allocated for the device, including fragmentation and
+    /// metadata; what the device really costs in RAM
+    pub mem_used_total: ByteSize,
+    /// Upper bound on `mem_used_total`, 0 when unlimited
+    pub mem_limit: ByteSize,
+    pub mem_used_max: ByteSize,
+    /// Pages filled with a single repeated value, stored without memory
+    pub same_pages: u64,
+    pub pages_compacted: u64,
+    /// Incompressible pages stored as is, missing before Linux 4.19
+    pub huge_pages: Option<u64>,
+    /// The selected compression algorithm, e.g. `lzo-rle` or `zstd`
+    pub algorithm: Option<String>,
+}
+
+impl ZramStats {
+    /// How many bytes of swapped out data each byte of RAM holds
+    pub fn compression_ratio(&self) -> Option<f64> {
+        if self.mem_used_total.0 == 0 {
+            None
+        } else {
+            Some(self.orig_data_size.0 as f64 / self.mem_used_total.0 as f64)
+        }
+    }
+}
+
+/// Hugepage pools of every supported page size and transparent hugepage
+/// usage
+#[derive(Debug, Clone, Default)]
+pub struct HugePages {
+    /// One pool per page size, smallest first
+    pub pools: Vec<HugePagePool>,
+    pub transparent: TransparentHugePages,
+}
+
+/// A persistent hugepage pool, from `/sys/kernel/mm/hugepages/hugepages-*`.
+///
+/// Counts are in pages of `page_size`.
+#[derive(Debug, Clone, Default)]
+pub struct HugePagePool {
+    pub page_size: ByteSize,
+    /// Pages in the pool, including surplus pages
+    pub total: u64,
+    pub free: u64,
+    /// Free pages promised to mappings but not yet faulted in
+    pub reserved: u64,
+    /// Pages allocated above `nr_hugepages` through overcommit
+    pub surplus: u64,
+}
+
+impl HugePagePool {
+    /// Pages faulted in or promised to mappings, `total - free + reserved`
+    pub fn used(&self) -> u64 {
+        self.total
+            .saturating_sub(self.free)
+            .saturating_add(self.reserved)
+            .min(self.total)
+    }
+
+    pub fn total_bytes(&self) -> ByteSize {
+        ByteSize::b(self.total * self.page_size.0)
+    }
+}
+
+/// Transparent hugepage settings from `/sys/kernel/mm/transparent_hugepage`
+/// and usage from `/proc/meminfo`.
+///
+/// Settings are `None` when the kernel is built without THP.
+#[derive(Debug, Clone, Default)]
+pub struct TransparentHugePages {
+    /// `always`, `madvise` or `never`
+    pub enabled: Option<String>,
+    /// `always`, `defer`, `defer+madvise`, `madvise` or `never`
+    pub defrag: Option<String>,
+    /// `always`, `within_size`, `advise`, `never`, `deny` or `force`
+    pub shmem_enabled: Option<String>,
+    /// Size of a PMD mapped transparent hugepage
+    pub pmd_size: Option<ByteSize>,
+    /// Anonymous memory backed by transparent hugepages (`AnonHugePages`)
+    pub anon: ByteSize,
+    /// tmpfs and shared memory backed by hugepages (`ShmemHugePages`)
+    pub shmem: ByteSize,
+    /// Page cache backed by hugepages (`FileHugePages`)
+    pub file: ByteSize,
+}
+
+/// Kernel slab caches, from `/proc/slabinfo` or `/sys/kernel/slab`
+#[derive(Debug, Clone, Default)]
+pub struct SlabInfo {
+    pub caches: Vec<SlabCache>,
+}
+
+impl SlabInfo {
+    /// Memory held by all slab caches
+    pub fn total(&self) -> ByteSize {
+        ByteSize::b(self.caches.iter().map(|cache| cache.size.0).sum())
+    }
+
+    /// The `n` caches holding the most memory, largest first
+    pub fn top_n(&self, n: usize) -> Vec<&SlabCache> {
+        let mut caches: Vec<&SlabCache> = self.caches.iter().collect();
+        caches.sort_by(|a, b| b.size.cmp(&a.size).then(a.name.cmp(&b.name)));
+        caches.truncate(n);
+        caches
+    }
+}
+
+/// A single slab cache
+#[derive(Debug, Clone, Default)]
+pub struct SlabCache {
+    pub name: String,
+    /// Objects in use
+    pub active_objs: u64,
+    /// Objects allocated, in use or not
+    pub num_objs: u64,
+    /// Size of a single object, including alignment and metadata
+    pub obj_size: ByteSize,
+    pub objs_per_slab: u64,
+    pub pages_per_slab: u64,
+    pub active_slabs: u64,
+    pub num_slabs: u64,
+    /// Memory held by the cache, `num_slabs * pages_per_slab * page size`
+    pub size: ByteSize,
+}
+
+impl SlabCache {
+    /// Memory taken by objects in use
+    pub fn active_size(&self) -> ByteSize {
+        ByteSize::b(self.active_objs * self.obj_size.0)
+    }
+}
+
+/// Counters from `/proc/vmstat`, keyed by name
+#[derive(Debug, Clone, Default)]
+pub struct VmStat {
+    pub counters: BTreeMap<String, u64>,
+}
+
+/// Reclaim is split by who does it, `pgscan_anon` and `pgscan_file` count the
+/// same pages again so they must not be added on top.
+const RECLAIMERS: [&str; 4] = ["kswapd", "direct", "khugepaged", "proactive"];
+
+impl VmStat {
+    #[inline(always)]
+    pub fn get(&self, name: &str) -> u64 {
+        self.counters.get(name).copied().unwrap_or(0)
+    }
+
+    /// Pages scanned for reclaim, by all reclaimers
+    pub fn pgscan(&self) -> u64 {
+        RECLAIMERS
+            .iter()
+            .map(|reclaimer| self.get(&format!("pgscan_{reclaimer}")))
+            .sum()
+    }
+
+    /// Pages reclaimed, by all reclaimers
+    pub fn pgsteal(&self) -> u64 {
+        RECLAIMERS
+            .iter()
+            .map(|reclaimer| self.get(&format!("pgsteal_{reclaimer}")))
+            .sum()
+    }
+
+    /// Computes per-second paging activity between `prev` and `self`
+    pub fn rates_since(&self, prev: &VmStat, elapsed: Duration) -> VmStatRates {
+        let secs = elapsed.as_secs_f64();
+        if secs == 0.0 {
+            return VmStatRates::default();
+        }
+        let rate =
+            |now: u64, before: u64| now.saturating_sub(before) as f64 / secs;
+        let counter = |name: &str| rate(self.get(name), prev.get(name));
+
+        VmStatRates {
+            pgfault: counter("pgfault"),
+            pgmajfault: counter("pgmajfault"),
+            pswpin: counter("pswpin"),
+            pswpout: counter("pswpout"),
+            pgscan: rate(self.pgscan(), prev.pgscan()),
+            pgsteal: rate(self.pgsteal(), prev.pgsteal()),
+            oom_kill: counter("oom_kill"),
+        }
+    }
+}
+
+/// Paging and swapping activity, per second
+#[derive(Debug, Clone, Default)]
+pub struct VmStatRates {
+    /// Page faults, minor and major
+    pub pgfault: f64,
+    /// Page faults that needed disk I/O
+    pub pgmajfault: f64,
+    /// Pages swapped in
+    pub pswpin: f64,
+    /// Pages swapped out
+    pub pswpout: f64,
+    /// Pages scanned for reclaim
+    pub pgscan: f64,
+    /// Pages reclaimed
+    pub pgsteal: f64,
+    /// Processes killed by the OOM killer
+    pub oom_kill: f64,
+}
+
+#[test]
+fn test_vmstat_rates() {
+    let vmstat = |values: &[(&str, u64)]| VmStat {
+        counters: values
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect(),
+    };
+    let prev = vmstat(&[
+        ("pgfault", 1000),
+        ("pgmajfault", 10),
+        ("pgscan_kswapd", 100),
+        ("pgscan_direct", 50),
+        ("pgscan_anon", 150),
+        ("pgsteal_kswapd", 80),
+        ("oom_kill", 1),
+    ]);
+    let now = vmstat(&[
+        ("pgfault", 3000),
+        ("pgmajfault", 30),
+        ("pgscan_kswapd", 300),
+        ("pgscan_direct", 250),
+        ("pgscan_anon", 550),
+        ("pgsteal_kswapd", 280),
+        ("oom_kill", 3),
+    ]);
+
+    let rates = now.rates_since(&prev, Duration::from_secs(2));
+    assert_eq!(rates.pgfault, 1000.0);
+    assert_eq!(rates.pgmajfault, 10.0);
+    assert_eq!(rates.pswpin, 0.0);
+    assert_eq!(rates.pgscan, 200.0);
+    assert_eq!(rates.pgsteal, 100.0);
+    assert_eq!(rates.oom_kill, 1.0);
+}
@@ -1,4 +1,8 @@
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::{
+    collections::BTreeMap,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
 
 use bytesize::ByteSize;
 
@@ -8,21 +12,185 @@ pub enum IpAddr {
     Unsupported,
     V4(Ipv4Addr),
     V6(Ipv6Addr),
+    /// Link layer address from an `AF_PACKET` or `AF_LINK` entry
+    Link(Vec<u8>),
+}
+
+impl IpAddr {
+    /// Converts to the standard library type, for V4 and V6 only
+    pub fn to_std(&self) -> Option<std::net::IpAddr> {
+        match self {
+            IpAddr::V4(addr) => Some(std::net::IpAddr::V4(*addr)),
+            IpAddr::V6(addr) => Some(std::net::IpAddr::V6(*addr)),
+            _ => None,
+        }
+    }
+
+    /// Number of leading one bits when used as a netmask, `None` for
+    /// non-contiguous masks
+    pub fn prefix_len(&self) -> Option<u8> {
+        let (ones, zeros, width) = match self {
+            IpAddr::V4(mask) => {
+                let bits = u32::from(*mask);
+                (bits.leading_ones(), bits.trailing_zeros(), u32::BITS)
+            }
+            IpAddr::V6(mask) => {
+                let bits = u128::from(*mask);
+                (bits.leading_ones(), bits.trailing_zeros(), u128::BITS)
+            }
+            _ => return None,
+        };
+        (ones + zeros == width).then_some(ones as u8)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct NetworkAddr {
     pub addr: IpAddr,
     pub netmask: IpAddr,
+    /// CIDR prefix length of `netmask`
+    pub prefix_len: Option<u8>,
+    /// Broadcast address, set on broadcast capable interfaces
+    pub broadcast: IpAddr,
+    /// Address of the other end of a point-to-point link
+    pub peer: IpAddr,
+    /// Interface index an IPv6 link-local address is scoped to
+    pub scope_id: Option<u32>,
+}
+
+#[cfg(feature = "ipnet")]
+impl NetworkAddr {
+    /// Converts `addr` and `prefix_len` to an `ipnet` network, keeping the
+    /// host bits of `addr`
+    pub fn to_ipnet(&self) -> Option<ipnet::IpNet> {
+        ipnet::IpNet::new(self.addr.to_std()?, self.prefix_len?).ok()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Network {
     pub name: String,
     pub addrs: Vec<NetworkAddr>,
+    pub flags: InterfaceFlags,
 }
 
+/// `IFF_*` interface flags, as reported by `getifaddrs` and
+/// `/sys/class/net/<if>/flags`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InterfaceFlags(pub u32);
+
+impl InterfaceFlags {
+    #[inline(always)]
+    pub fn contains(&self, flag: libc::c_int) -> bool {
+        self.0 & flag as u32 != 0
+    }
+
+    /// Administratively up
+    pub fn is_up(&self) -> bool {
+        self.contains(libc::IFF_UP)
+    }
+
+    /// Up and with resources allocated by the driver
+    pub fn is_running(&self) -> bool {
+        self.contains(libc::IFF_RUNNING)
+    }
+
+    pub fn is_loopback(&self) -> bool {
+        self.contains(libc::IFF_LOOPBACK)
+    }
+
+    pub fn is_point_to_point(&self) -> bool {
+        self.contains(libc::IFF_POINTOPOINT)
+    }
+
+    pub fn is_broadcast(&self) -> bool {
+        self.contains(libc::IFF_BROADCAST)
+    }
+
+    pub fn is_multicast(&self) -> bool {
+        self.contains(libc::IFF_MULTICAST)
+    }
+
+    pub fn is_promiscuous(&self) -> bool {
+        self.contains(libc::IFF_PROMISC)
+    }
+}
+
+/// Link level state and configuration of an interface
 #[derive(Debug, Clone)]
+pub struct NetworkLink {
+    pub name: String,
+    pub ifindex: u32,
+    pub kind: InterfaceKind,
+    /// Hardware address, e.g. `02:fc:00:00:00:01`
+    pub mac: Option<String>,
+    pub mtu: u32,
+    /// Negotiated speed in Mbit/s, `None` when the driver doesn't know it
+    pub speed: Option<u32>,
+    pub duplex: Duplex,
+    pub operstate: OperState,
+    /// Whether the physical link is up, `None` while the interface is down
+    pub carrier: Option<bool>,
+    pub flags: InterfaceFlags,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterfaceKind {
+    Physical,
+    Loopback,
+    Bridge,
+    Bond,
+    Vlan,
+    Veth,
+    Tun,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Duplex {
+    Full,
+    Half,
+    Unknown,
+}
+
+impl From<&str> for Duplex {
+    fn from(duplex: &str) -> Duplex {
+        match duplex {
+            "full" => Duplex::Full,
+            "half" => Duplex::Half,
+            _ => Duplex::Unknown,
+        }
+    }
+}
+
+/// RFC 2863 operational state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperState {
+    Unknown,
+    NotPresent,
+    Down,
+    LowerLayerDown,
+    Testing,
+    Dormant,
+    Up,
+}
+
+impl From<&str> for OperState {
+    fn from(state: &str) -> OperState {
+        match state {
+            "notpresent" => OperState::NotPresent,
+            "down" => OperState::Down,
+            "lowerlayerdown" => OperState::LowerLayerDown,
+            "testing" => OperState::Testing,
+            "dormant" => OperState::Dormant,
+            "up" => OperState::Up,
+            _ => OperState::Unknown,
+        }
+    }
+}
+
+/// Interface counters, in the order of `/proc/net/dev`
+#[derive(Debug, Clone, Default)]
 pub struct NetworkStats {
     pub rx_bytes: ByteSize,
     pub tx_bytes: ByteSize,
@@ -30,16 +198,629 @@ pub struct NetworkStats {
     pub tx_packets: u64,
     pub rx_errors: u64,
     pub tx_errors: u64,
+    /// Packets received but dropped, e.g. for lack of buffer space
+    pub rx_dropped: u64,
+    /// Receive FIFO overruns
+    pub rx_fifo: u64,
+    /// Frame alignment errors
+    pub rx_frame: u64,
+    pub rx_compressed: u64,
+    /// Multicast packets received
+    pub multicast: u64,
+    pub tx_dropped: u64,
+    /// Transmit FIFO underruns
+    pub tx_fifo: u64,
+    pub collisions: u64,
+    /// Carrier losses while transmitting
+    pub tx_carrier: u64,
+    pub tx_compressed: u64,
+}
+
+/// Counter delta that survives the counter wrapping or being reset.
+///
+/// Drivers on 32-bit kernels keep `unsigned long` counters that wrap at
+/// 2^32, so a decrease from the upper half of the 32-bit range is taken as
+/// a wrap. Any other decrease means the counter was reset, typically
+/// because the interface was recreated, and everything counted since is the
+/// delta. A reset from that upper half can't be told apart from a wrap.
+fn counter_delta(now: u64, before: u64) -> u64 {
+    const U32_MAX: u64 = u32::MAX as u64;
+    match now.checked_sub(before) {
+        Some(delta) => delta,
+        None if before > U32_MAX / 2 && before <= U32_MAX => {
+            U32_MAX - before + now + 1
+        }
+        None => now,
+    }
+}
+
+impl NetworkStats {
+    /// Computes per-second rates from the counters accumulated between
+    /// `prev` and `self` over `elapsed`.
+    pub fn rates_since(
+        &self,
+        prev: &NetworkStats,
+        elapsed: Duration,
+    ) -> NetworkRates {
+        let secs = elapsed.as_secs_f64();
+        if secs == 0.0 {
+            return NetworkRates::default();
+        }
+        let rate =
+            |now: u64, before: u64| counter_delta(now, before) as f64 / secs;
+
+        NetworkRates {
+            rx_bytes: rate(self.rx_bytes.0, prev.rx_bytes.0),
+            tx_bytes: rate(self.tx_bytes.0, prev.tx_bytes.0),
+            rx_packets: rate(self.rx_packets, prev.rx_packets),
+            tx_packets: rate(self.tx_packets, prev.tx_packets),
+            rx_errors: rate(self.rx_errors, prev.rx_errors),
+            tx_errors: rate(self.tx_errors, prev.tx_errors),
+            rx_dropped: rate(self.rx_dropped, prev.rx_dropped),
+            tx_dropped: rate(self.tx_dropped, prev.tx_dropped),
+        }
+    }
+}
+
+/// Interface throughput, per second
+#[derive(Debug, Clone, Default)]
+pub struct NetworkRates {
+    pub rx_bytes: f64,
+    pub tx_bytes: f64,
+    pub rx_packets: f64,
+    pub tx_packets: f64,
+    pub rx_errors: f64,
+    pub tx_errors: f64,
+    pub rx_dropped: f64,
+    pub tx_dropped: f64,
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct SocketStats {
     pub tcp_sockets_in_use: usize,
+    /// TCP and TCP6 sockets closed by their process that the kernel still
+    /// holds, e.g. to send remaining data, as `orphan` in
+    /// `/proc/net/sockstat`
     pub tcp_sockets_orphan: usize,
+    /// TCP and TCP6 sockets in TIME_WAIT, as `tw` in `/proc/net/sockstat`
     pub tcp_sockets_time_wait: usize,
     pub udp_sockets_in_use: usize,
     pub tcp6_sockets_in_use: usize,
     pub udp6_sockets_in_use: usize,
+    /// TCP and TCP6 sockets by state
+    pub tcp_states: BTreeMap<TcpState, usize>,
+    pub raw_sockets_in_use: usize,
+    pub raw6_sockets_in_use: usize,
+    /// SCTP associations, zero unless the sctp module is loaded
+    pub sctp_associations: usize,
+    /// SCTP endpoints, zero unless the sctp module is loaded
+    pub sctp_endpoints: usize,
+    /// Empty when `/proc/net/unix` can't be read
+    pub unix: UnixSocketStats,
+}
+
+/// Unix domain socket counts, from `/proc/net/unix`
+#[derive(Debug, Default, Clone)]
+pub struct UnixSocketStats {
+    pub stream: BTreeMap<UnixSocketState, usize>,
+    pub dgram: BTreeMap<UnixSocketState, usize>,
+    pub seqpacket: BTreeMap<UnixSocketState, usize>,
+    /// Sockets by bound path, both listeners and the connections they
+    /// accepted. Abstract names start with `@`
+    pub by_path: BTreeMap<String, usize>,
+}
+
+impl UnixSocketStats {
+    pub fn total(&self) -> usize {
+        [&self.stream, &self.dgram, &self.seqpacket]
+            .iter()
+            .flat_map(|states| states.values())
+            .sum()
+    }
+}
+
+impl<'a> FromIterator<&'a UnixSocket> for UnixSocketStats {
+    fn from_iter<I: IntoIterator<Item = &'a UnixSocket>>(iter: I) -> Self {
+        let mut stats = UnixSocketStats::default();
+        for socket in iter {
+            let states = match socket.socket_type {
+                UnixSocketType::Stream => &mut stats.stream,
+                UnixSocketType::Dgram => &mut stats.dgram,
+                UnixSocketType::SeqPacket => &mut stats.seqpacket,
+                UnixSocketType::Unknown(_) => continue,
+            };
+            *states.entry(socket.state).or_insert(0) += 1;
+            if let Some(path) = &socket.path {
+                *stats.by_path.entry(path.clone()).or_insert(0) += 1;
+            }
+        }
+        stats
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UnixSocketType {
+    Stream,
+    Dgram,
+    SeqPacket,
+    Unknown(u16),
+}
+
+impl From<u16> for UnixSocketType {
+    fn from(socket_type: u16) -> UnixSocketType {
+        match socket_type {
+            1 => UnixSocketType::Stream,
+            2 => UnixSocketType::Dgram,
+            5 => UnixSocketType::SeqPacket,
+            x => UnixSocketType::Unknown(x),
+        }
+    }
+}
+
+/// Unix domain socket state, `socket_state` from `include/uapi/linux/net.h`.
+///
+/// Sockets accepting connections are `Listening` rather than `Unconnected`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UnixSocketState {
+    Free,
+    Unconnected,
+    Connecting,
+    Connected,
+    Disconnecting,
+    Listening,
+    Unknown(u8),
+}
+
+impl From<u8> for UnixSocketState {
+    fn from(state: u8) -> UnixSocketState {
+        match state {
+            0 => UnixSocketState::Free,
+            1 => UnixSocketState::Unconnected,
+            2 => UnixSocketState::Connecting,
+            3 => UnixSocketState::Connected,
+            4 => UnixSocketState::Disconnecting,
+            x => UnixSocketState::Unknown(x),
+        }
+    }
+}
+
+/// A single unix domain socket, from `/proc/net/unix`
+#[derive(Debug, Clone)]
+pub struct UnixSocket {
+    pub socket_type: UnixSocketType,
+    pub state: UnixSocketState,
+    pub ref_count: u32,
+    /// Socket inode, matches the `socket:[inode]` links in `/proc/<pid>/fd`
+    pub inode: u64,
+    /// Bound path, `None` for unnamed sockets
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SocketProtocol {
+    Tcp,
+    Tcp6,
+    Udp,
+    Udp6,
+}
+
+/// Kernel socket state, from `include/net/tcp_states.h`.
+///
+/// UDP sockets reuse it: connected sockets are `Established` and all others
+/// `Close`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TcpState {
+    Established,
+    SynSent,
+    SynRecv,
+    FinWait1,
+    FinWait2,
+    TimeWait,
+    Close,
+    CloseWait,
+    LastAck,
+    Listen,
+    Closing,
+    NewSynRecv,
+    Unknown(u8),
+}
+
+impl From<u8> for TcpState {
+    fn from(state: u8) -> TcpState {
+        match state {
+            0x01 => TcpState::Established,
+            0x02 => TcpState::SynSent,
+            0x03 => TcpState::SynRecv,
+            0x04 => TcpState::FinWait1,
+            0x05 => TcpState::FinWait2,
+            0x06 => TcpState::TimeWait,
+            0x07 => TcpState::Close,
+            0x08 => TcpState::CloseWait,
+            0x09 => TcpState::LastAck,
+            0x0A => TcpState::Listen,
+            0x0B => TcpState::Closing,
+            0x0C => TcpState::NewSynRecv,
+            x => TcpState::Unknown(x),
+        }
+    }
+}
+
+/// The socket timer that is pending, if any
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocketTimer {
+    None,
+    Retransmit,
+    KeepAlive,
+    TimeWait,
+    ZeroWindowProbe,
+    Unknown(u8),
+}
+
+impl From<u8> for SocketTimer {
+    fn from(timer: u8) -> SocketTimer {
+        match timer {
+            0 => SocketTimer::None,
+            1 => SocketTimer::Retransmit,
+            2 => SocketTimer::KeepAlive,
+            3 => SocketTimer::TimeWait,
+            4 => SocketTimer::ZeroWindowProbe,
+            x => SocketTimer::Unknown(x),
+        }
+    }
+}
+
+/// A single socket, from `/proc/net/{tcp,tcp6,udp,udp6}`
+#[derive(Debug, Clone)]
+pub struct Socket {
+    pub protocol: SocketProtocol,
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    pub state: TcpState,
+    /// Bytes waiting to be sent, or acknowledged for TCP
+    pub tx_queue: u64,
+    /// Bytes waiting to be read
+    pub rx_queue: u64,
+    pub timer: SocketTimer,
+    /// Time until `timer` fires
+    pub timer_expires: Duration,
+    /// Unrecovered retransmit timeouts
+    pub retransmits: u64,
+    pub uid: u32,
+    /// Socket inode, matches the `socket:[inode]` links in `/proc/<pid>/fd`
+    pub inode: u64,
+    /// TCP internals, only available through netlink
+    pub tcp_info: Option<TcpInfo>,
+}
+
+/// TCP connection internals from `struct tcp_info`, as `ss -ti` shows them
+#[derive(Debug, Clone, Default)]
+pub struct TcpInfo {
+    /// Smoothed round trip time
+    pub rtt: Duration,
+    /// Round trip time variance
+    pub rtt_var: Duration,
+    /// Retransmission timeout
+    pub rto: Duration,
+    /// Congestion window, in segments
+    pub snd_cwnd: u32,
+    /// Slow start threshold, in segments
+    pub snd_ssthresh: u32,
+    pub snd_mss: u32,
+    pub rcv_mss: u32,
+    /// Path MTU
+    pub pmtu: u32,
+    /// Consecutive timeouts of the segment being retransmitted
+    pub retransmits: u8,
+    /// Segments sent and not yet acknowledged
+    pub unacked: u32,
+    /// Segments considered lost
+    pub lost: u32,
+    /// Segments being retransmitted
+    pub retrans: u32,
+    /// Segments retransmitted over the connection's lifetime
+    pub total_retrans: u32,
+    /// Receive window the connection advertises
+    pub rcv_space: u32,
+    pub last_data_sent: Duration,
+    pub last_data_recv: Duration,
+}
+
+/// Counters of one section of `/proc/net/snmp`, `/proc/net/snmp6` or
+/// `/proc/net/netstat`, keyed by name without the section prefix
+#[derive(Debug, Clone, Default)]
+pub struct ProtocolSection {
+    pub counters: BTreeMap<String, i64>,
+}
+
+impl ProtocolSection {
+    #[inline(always)]
+    pub fn get(&self, name: &str) -> i64 {
+        self.counters.get(name).copied().unwrap_or(0)
+    }
+
+    pub fn set(&mut self, name: &str, value: i64) {
+        self.counters.insert(name.to_string(), value);
+    }
+}
+
+/// The `Ip` section of `/proc/net/snmp`
+#[derive(Debug, Clone, Default)]
+pub struct IpCounters {
+    pub in_receives: i64,
+    pub in_hdr_errors: i64,
+    pub in_addr_errors: i64,
+    pub in_discards: i64,
+    pub in_delivers: i64,
+    pub out_requests: i64,
+    pub out_discards: i64,
+    pub out_no_routes: i64,
+    /// Datagrams that failed to reassemble from fragments
+    pub reasm_fails: i64,
+    /// Counters without a field above, e.g. `Forwarding`
+    pub other: ProtocolSection,
+}
+
+impl IpCounters {
+    pub fn set(&mut self, name: &str, value: i64) {
+        match name {
+            "InReceives" => self.in_receives = value,
+            "InHdrErrors" => self.in_hdr_errors = value,
+            "InAddrErrors" => self.in_addr_errors = value,
+            "InDiscards" => self.in_discards = value,
+            "InDelivers" => self.in_delivers = value,
+            "OutRequests" => self.out_requests = value,
+            "OutDiscards" => self.out_discards = value,
+            "OutNoRoutes" => self.out_no_routes = value,
+            "ReasmFails" => self.reasm_fails = value,
+            other => self.other.set(other, value),
+        }
+    }
+}
+
+/// The `Icmp` section of `/proc/net/snmp`
+#[derive(Debug, Clone, Default)]
+pub struct IcmpCounters {
+    pub in_msgs: i64,
+    pub in_errors: i64,
+    pub in_dest_unreachs: i64,
+    pub out_msgs: i64,
+    pub out_errors: i64,
+    pub out_dest_unreachs: i64,
+    /// Counters without a field above, e.g. `InEchos`
+    pub other: ProtocolSection,
+}
+
+impl IcmpCounters {
+    pub fn set(&mut self, name: &str, value: i64) {
+        match name {
+            "InMsgs" => self.in_msgs = value,
+            "InErrors" => self.in_errors = value,
+            "InDestUnreachs" => self.in_dest_unreachs = value,
+            "OutMsgs" => self.out_msgs = value,
+            "OutErrors" => self.out_errors = value,
+            "OutDestUnreachs" => self.out_dest_unreachs = value,
+            other => self.other.set(other, value),
+        }
+    }
+}
+
+/// The `Tcp` section of `/proc/net/snmp`
+#[derive(Debug, Clone, Default)]
+pub struct TcpCounters {
+    pub active_opens: i64,
+    pub passive_opens: i64,
+    pub in_segs: i64,
+    pub out_segs: i64,
+    pub retrans_segs: i64,
+    pub in_errs: i64,
+    pub out_rsts: i64,
+    /// Counters without a field above, e.g. `MaxConn`
+    pub other: ProtocolSection,
+}
+
+impl TcpCounters {
+    pub fn set(&mut self, name: &str, value: i64) {
+        match name {
+            "ActiveOpens" => self.active_opens = value,
+            "PassiveOpens" => self.passive_opens = value,
+            "InSegs" => self.in_segs = value,
+            "OutSegs" => self.out_segs = value,
+            "RetransSegs" => self.retrans_segs = value,
+            "InErrs" => self.in_errs = value,
+            "OutRsts" => self.out_rsts = value,
+            other => self.other.set(other, value),
+        }
+    }
+}
+
+/// The `TcpExt` section of `/proc/net/netstat`
+#[derive(Debug, Clone, Default)]
+pub struct TcpExtCounters {
+    pub listen_overflows: i64,
+    pub listen_drops: i64,
+    pub tcp_timeouts: i64,
+    /// Counters without a field above
+    pub other: ProtocolSection,
+}
+
+impl TcpExtCounters {
+    pub fn set(&mut self, name: &str, value: i64) {
+        match name {
+            "ListenOverflows" => self.listen_overflows = value,
+            "ListenDrops" => self.listen_drops = value,
+            "TCPTimeouts" => self.tcp_timeouts = value,
+            other => self.other.set(other, value),
+        }
+    }
+}
+
+/// The `Udp` section of `/proc/net/snmp` or the `Udp6` counters of
+/// `/proc/net/snmp6`
+#[derive(Debug, Clone, Default)]
+pub struct UdpCounters {
+    pub in_datagrams: i64,
+    pub out_datagrams: i64,
+    pub no_ports: i64,
+    pub in_errors: i64,
+    pub rcvbuf_errors: i64,
+    pub sndbuf_errors: i64,
+    /// Counters without a field above
+    pub other: ProtocolSection,
+}
+
+impl UdpCounters {
+    pub fn set(&mut self, name: &str, value: i64) {
+        match name {
+            "InDatagrams" => self.in_datagrams = value,
+            "OutDatagrams" => self.out_datagrams = value,
+            "NoPorts" => self.no_ports = value,
+            "InErrors" => self.in_errors = value,
+            "RcvbufErrors" => self.rcvbuf_errors = value,
+            "SndbufErrors" => self.sndbuf_errors = value,
+            other => self.other.set(other, value),
+        }
+    }
+}
+
+/// Protocol counters of the network stack, as `netstat -s` shows them
+#[derive(Debug, Clone, Default)]
+pub struct ProtocolCounters {
+    pub ip: IpCounters,
+    pub icmp: IcmpCounters,
+    pub tcp: TcpCounters,
+    pub udp: UdpCounters,
+    pub udp_lite: ProtocolSection,
+    /// Linux specific TCP counters such as `ListenOverflows`
+    pub tcp_ext: TcpExtCounters,
+    pub ip_ext: ProtocolSection,
+    pub ip6: ProtocolSection,
+    pub icmp6: ProtocolSection,
+    pub udp6: UdpCounters,
+    pub udp_lite6: ProtocolSection,
+    /// Sections not listed above, e.g. `IcmpMsg` or `MPTcpExt`
+    pub other: BTreeMap<String, ProtocolSection>,
+}
+
+impl ProtocolCounters {
+    /// Stores counter `name` of `section`, both as the kernel spells them
+    pub fn set(&mut self, section: &str, name: &str, value: i64) {
+        match section {
+            "Ip" => self.ip.set(name, value),
+            "Icmp" => self.icmp.set(name, value),
+            "Tcp" => self.tcp.set(name, value),
+            "Udp" => self.udp.set(name, value),
+            "UdpLite" => self.udp_lite.set(name, value),
+            "TcpExt" => self.tcp_ext.set(name, value),
+            "IpExt" => self.ip_ext.set(name, value),
+            "Ip6" => self.ip6.set(name, value),
+            "Icmp6" => self.icmp6.set(name, value),
+            "Udp6" => self.udp6.set(name, value),
+            "UdpLite6" => self.udp_lite6.set(name, value),
+            other => self
+                .other
+                .entry(other.to_string())
+                .or_default()
+                .set(name, value),
+        }
+    }
+
+    /// Computes per-second rates of the counters worth alerting on between
+    /// `prev` and `self`
+    pub fn rates_since(
+        &self,
+        prev: &ProtocolCounters,
+        elapsed: Duration,
+    ) -> ProtocolRates {
+        let secs = elapsed.as_secs_f64();
+        if secs == 0.0 {
+            return ProtocolRates::default();
+        }
+        let rate = |now: i64, before: i64| {
+            now.saturating_sub(before).max(0) as f64 / secs
+        };
+        let tcp = |field: fn(&TcpCounters) -> i64| {
+            rate(field(&self.tcp), field(&prev.tcp))
+        };
+        let tcp_ext = |field: fn(&TcpExtCounters) -> i64| {
+            rate(field(&self.tcp_ext), field(&prev.tcp_ext))
+        };
+        let udp = |field: fn(&UdpCounters) -> i64| {
+            rate(
+                field(&self.udp) + field(&self.udp6),
+                field(&prev.udp) + field(&prev.udp6),
+            )
+        };
+
+        ProtocolRates {
+            tcp_active_opens: tcp(|tcp| tcp.active_opens),
+            tcp_passive_opens: tcp(|tcp| tcp.passive_opens),
+            tcp_in_segs: tcp(|tcp| tcp.in_segs),
+            tcp_out_segs: tcp(|tcp| tcp.out_segs),
+            tcp_retrans_segs: tcp(|tcp| tcp.retrans_segs),
+            tcp_in_errs: tcp(|tcp| tcp.in_errs),
+            tcp_out_rsts: tcp(|tcp| tcp.out_rsts),
+            tcp_listen_overflows: tcp_ext(|ext| ext.listen_overflows),
+            tcp_listen_drops: tcp_ext(|ext| ext.listen_drops),
+            tcp_timeouts: tcp_ext(|ext| ext.tcp_timeouts),
+            udp_in_datagrams: udp(|udp| udp.in_datagrams),
+            udp_out_datagrams: udp(|udp| udp.out_datagrams),
+            udp_no_ports: udp(|udp| udp.no_ports),
+            udp_in_errors: udp(|udp| udp.in_errors),
+            udp_rcvbuf_errors: udp(|udp| udp.rcvbuf_errors),
+            udp_sndbuf_errors: udp(|udp| udp.sndbuf_errors),
+        }
+    }
+}
+
+/// Network stack activity, per second. UDP rates include UDP over IPv6
+#[derive(Debug, Clone, Default)]
+pub struct ProtocolRates {
+    /// Connections opened by this host
+    pub tcp_active_opens: f64,
+    /// Connections accepted by this host
+    pub tcp_passive_opens: f64,
+    pub tcp_in_segs: f64,
+    pub tcp_out_segs: f64,
+    pub tcp_retrans_segs: f64,
+    pub tcp_in_errs: f64,
+    pub tcp_out_rsts: f64,
+    /// Connections dropped because a listen queue was full
+    pub tcp_listen_overflows: f64,
+    /// Connections dropped by listening sockets for any reason
+    pub tcp_listen_drops: f64,
+    pub tcp_timeouts: f64,
+    pub udp_in_datagrams: f64,
+    pub udp_out_datagrams: f64,
+    /// Datagrams to a port nobody listens on
+    pub udp_no_ports: f64,
+    pub udp_in_errors: f64,
+    /// Datagrams dropped because a receive buffer was full
+    pub udp_rcvbuf_errors: f64,
+    pub udp_sndbuf_errors: f64,
+}
+
+/// A process holding a socket open
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SocketOwner {
+    pub pid: u32,
+    /// Command name, from `/proc/<pid>/comm`
+    pub name: String,
+    pub fd: u32,
+}
+
+/// A listening TCP socket or a bound UDP socket, like `ss -tulnp` lists
+#[derive(Debug, Clone)]
+pub struct ListeningPort {
+    pub socket: Socket,
+    /// Processes with the socket open, several after a fork. Empty when
+    /// the owner's `/proc/<pid>/fd` can't be read
+    pub owners: Vec<SocketOwner>,
+}
+
+impl ListeningPort {
+    pub fn port(&self) -> u16 {
+        self.socket.local.port()
+    }
 }
 
 impl SocketStats {
@@ -73,3 +854,136 @@ impl SocketStats {
         self
     }
 }
+
+#[test]
+fn test_network_rates() {
+    let prev = NetworkStats {
+        rx_bytes: ByteSize::b(1_000),
+        tx_bytes: ByteSize::b(u64::from(u32::MAX) - 999),
+        rx_packets: 5_000_000_000,
+        tx_packets: 100,
+        rx_dropped: 7,
+        tx_dropped: 3_000_000_000,
+        ..Default::default()
+    };
+    let now = NetworkStats {
+        rx_bytes: ByteSize::b(3_000),
+        tx_bytes: ByteSize::b(1_000),
+        rx_packets: 400,
+        tx_packets: 100,
+        rx_dropped: 3,
+        tx_dropped: 1_000,
+        ..Default::default()
+    };
+
+    let rates = now.rates_since(&prev, Duration::from_secs(2));
+    assert_eq!(rates.rx_bytes, 1_000.0);
+    // 32-bit counter wrapped
+    assert_eq!(rates.tx_bytes, 1_000.0);
+    // Dropping from about 3 GB is a wrap too
+    assert_eq!(rates.tx_dropped, (1_294_967_296.0 + 1_000.0) / 2.0);
+    // 64-bit counter went backwards, the interface was recreated
+    assert_eq!(rates.rx_packets, 200.0);
+    assert_eq!(rates.tx_packets, 0.0);
+    // Small value went backwards, also a reset
+    assert_eq!(rates.rx_dropped, 1.5);
+}
+
+#[test]
+fn test_ip_addr_prefix_len() {
+    let v4 = |mask: &str| IpAddr::V4(mask.parse().unwrap());
+    let v6 = |mask: &str| IpAddr::V6(mask.parse().unwrap());
+    assert_eq!(v4("255.255.255.0").prefix_len(), Some(24));
+    assert_eq!(v4("255.255.255.255").prefix_len(), Some(32));
+    assert_eq!(v4("0.0.0.0").prefix_len(), Some(0));
+    assert_eq!(v4("255.0.255.0").prefix_len(), None);
+    assert_eq!(v6("ffff:ffff:ffff:ffff::").prefix_len(), Some(64));
+    assert_eq!(
+        v6("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff").prefix_len(),
+        Some(128)
+    );
+    assert_eq!(IpAddr::Empty.prefix_len(), None);
+
+    assert_eq!(
+        v4("10.0.0.1").to_std(),
+        Some(std::net::IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)))
+    );
+    assert_eq!(IpAddr::Link(vec![2, 0, 0, 0, 0, 1]).to_std(), None);
+}
+
+#[cfg(feature = "ipnet")]
+#[test]
+fn test_network_addr_to_ipnet() {
+    let addr = NetworkAddr {
+        addr: IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)),
+        netmask: IpAddr::V4(Ipv4Addr::new(255, 255, 255, 0)),
+        prefix_len: Some(24),
+        broadcast: IpAddr::V4(Ipv4Addr::new(192, 168, 1, 255)),
+        peer: IpAddr::Empty,
+        scope_id: None,
+    };
+    let net = addr.to_ipnet().unwrap();
+    assert_eq!(net.to_string(), "192.168.1.20/24");
+    assert_eq!(net.trunc().to_string(), "192.168.1.0/24");
+}
+
+#[test]
+fn test_protocol_rates() {
+    let counters = |tcp: &[(&str, i64)], udp6: &[(&str, i64)]| {
+        let mut counters = ProtocolCounters::default();
+        for (section, values) in [("Tcp", tcp), ("Udp6", udp6)] {
+            for (name, value) in values {
+                counters.set(section, name, *value);
+            }
+        }
+        counters
+    };
+    let prev = counters(
+        &[("RetransSegs", 100), ("MaxConn", -1)],
+        &[("RcvbufErrors", 10)],
+    );
+    let now = counters(
+        &[("RetransSegs", 300), ("MaxConn", -1)],
+        &[("RcvbufErrors", 50)],
+    );
+
+    let rates = now.rates_since(&prev, Duration::from_secs(2));
+    assert_eq!(rates.tcp_retrans_segs, 100.0);
+    assert_eq!(rates.udp_rcvbuf_errors, 20.0);
+    assert_eq!(rates.tcp_listen_overflows, 0.0);
+    assert_eq!(now.tcp.other.get("MaxConn"), -1);
+}
+
+#[test]
+fn test_unix_socket_stats() {
+    let socket = |socket_type, state, path: Option<&str>| UnixSocket {
+        socket_type,
+        state,
+        ref_count: 2,
+        inode: 0,
+        path: path.map(str::to_string),
+    };
+    let sockets = [
+        socket(
+            UnixSocketType::Stream,
+            UnixSocketState::Listening,
+            Some("/run/app.sock"),
+        ),
+        socket(
+            UnixSocketType::Stream,
+            UnixSocketState::Connected,
+            Some("/run/app.sock"),
+        ),
+        socket(UnixSocketType::Stream, UnixSocketState::Connected, None),
+        socket(UnixSocketType::Dgram, UnixSocketState::Connected, None),
+        socket(UnixSocketType::Unknown(3), UnixSocketState::Connected, None),
+    ];
+
+    let stats: UnixSocketStats = sockets.iter().collect();
+    assert_eq!(stats.total(), 4);
+    assert_eq!(stats.stream[&UnixSocketState::Connected], 2);
+    assert_eq!(stats.stream[&UnixSocketState::Listening], 1);
+    assert_eq!(stats.dgram[&UnixSocketState::Connected], 1);
+    assert_eq!(stats.by_path["/run/app.sock"], 2);
+    assert_eq!(stats.by_path.len(), 1);
+}
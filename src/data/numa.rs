@@ -0,0 +1,20 @@
+use std::collections::BTreeMap;
+
+use bytesize::ByteSize;
+
+/// Memory and CPUs of a single NUMA node
+#[derive(Debug, Clone)]
+pub struct NumaNode {
+    pub id: usize,
+    pub total: ByteSize,
+    pub free: ByteSize,
+    pub used: ByteSize,
+    /// Kernel ids of the CPUs that belong to the node, the `N` of `cpuN`.
+    /// These are not indices into the per-core `cpu_load()` vector, which
+    /// skips offline CPUs and gaps in the numbering
+    pub cpus: Vec<usize>,
+    /// The node's own `meminfo`, keyed like `/proc/meminfo`
+    pub meminfo: BTreeMap<String, ByteSize>,
+    /// Allocation counters such as `numa_hit`, `numa_miss` and `numa_foreign`
+    pub numastat: BTreeMap<String, u64>,
+}
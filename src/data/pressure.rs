@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+/// One line of a Pressure Stall Information file
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PressureStats {
+    /// Share of time stalled over the last 10 seconds, in percent
+    pub avg10: f32,
+    /// Share of time stalled over the last 60 seconds, in percent
+    pub avg60: f32,
+    /// Share of time stalled over the last 300 seconds, in percent
+    pub avg300: f32,
+    /// Total time stalled
+    pub total: Duration,
+}
+
+/// Pressure of a single resource.
+///
+/// `some` is the time at least one task was stalled on the resource, `full`
+/// the time all non-idle tasks were stalled at once. Either may be missing:
+/// `irq` only reports `full`, and `cpu` only reports `full` since Linux 5.13.
+#[derive(Debug, Clone, Default)]
+pub struct Pressure {
+    pub some: Option<PressureStats>,
+    pub full: Option<PressureStats>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SystemPressure {
+    pub cpu: Pressure,
+    pub memory: Pressure,
+    pub io: Pressure,
+    /// Only available with `CONFIG_IRQ_TIME_ACCOUNTING` on Linux 6.1+
+    pub irq: Option<Pressure>,
+}
@@ -39,22 +39,22 @@ impl fmt::Display for ProcessStatus {
     }
 }
 
-impl Into<u64> for ProcessStatus {
-    fn into(self) -> u64 {
-        match self {
-            ProcessStatus::Idle => (0 << 32) | 0,
-            ProcessStatus::Run => (1 << 32) | 0,
-            ProcessStatus::Sleep => (2 << 32) | 0,
-            ProcessStatus::Stop => (3 << 32) | 0,
-            ProcessStatus::Zombie => (4 << 32) | 0,
-            ProcessStatus::Tracing => (5 << 32) | 0,
-            ProcessStatus::Dead => (6 << 32) | 0,
-            ProcessStatus::Wakekill => (7 << 32) | 0,
-            ProcessStatus::Waking => (8 << 32) | 0,
-            ProcessStatus::Parked => (9 << 32) | 0,
-            ProcessStatus::LockBlocked => (10 << 32) | 0,
-            ProcessStatus::UninterruptibleDiskSleep => (11 << 32) | 0,
-            ProcessStatus::Suspended => (12 << 32) | 0,
+impl From<ProcessStatus> for u64 {
+    fn from(status: ProcessStatus) -> u64 {
+        match status {
+            ProcessStatus::Idle => 0,
+            ProcessStatus::Run => 1 << 32,
+            ProcessStatus::Sleep => 2 << 32,
+            ProcessStatus::Stop => 3 << 32,
+            ProcessStatus::Zombie => 4 << 32,
+            ProcessStatus::Tracing => 5 << 32,
+            ProcessStatus::Dead => 6 << 32,
+            ProcessStatus::Wakekill => 7 << 32,
+            ProcessStatus::Waking => 8 << 32,
+            ProcessStatus::Parked => 9 << 32,
+            ProcessStatus::LockBlocked => 10 << 32,
+            ProcessStatus::UninterruptibleDiskSleep => 11 << 32,
+            ProcessStatus::Suspended => 12 << 32,
             ProcessStatus::Unknown(code) => (13 << 32) | code as u64,
         }
     }
@@ -1,9 +1,10 @@
 use std::{
     fs,
     io::{self, Read},
+    path::Path,
 };
 
-pub fn read_file(path: &str) -> io::Result<String> {
+pub fn read_file<P: AsRef<Path>>(path: P) -> io::Result<String> {
     let mut s = String::new();
     fs::File::open(path)
         .and_then(|mut f| f.read_to_string(&mut s))
@@ -1,7 +1,11 @@
+#[cfg(feature = "tokio")]
+pub mod asynchronous;
 pub mod data;
 pub(crate) mod helper;
 pub mod platform;
 
+#[cfg(feature = "tokio")]
+pub use self::asynchronous::AsyncMeasurement;
 pub use self::data::*;
 pub use self::platform::interface::Measurement;
 pub use self::platform::MeasurementImpl as PlatformMeasurement;
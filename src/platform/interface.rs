@@ -1,7 +1,11 @@
 use crate::{
     data::*,
-    disk::FileSystem,
-    network::{Network, NetworkStats, SocketStats},
+    disk::{BlockDeviceLoad, BlockDeviceStats, FileSystem},
+    network::{
+        ListeningPort, Network, NetworkLink, NetworkRates, NetworkStats,
+        ProtocolCounters, ProtocolRates, Socket, SocketStats, UnixSocket,
+    },
+    numa::NumaNode,
     process::ProcessInfo,
 };
 use std::{collections::BTreeMap, io, path, time::Duration};
@@ -10,6 +14,8 @@ use time::OffsetDateTime;
 pub trait Measurement {
     fn new() -> Self;
 
+    fn cpu_time(&self) -> io::Result<CpuSnapshot>;
+
     fn cpu_load(
         &self,
     ) -> std::io::Result<DelayedMeasurement<Vec<SystemCpuLoad>>>;
@@ -18,16 +24,11 @@ pub trait Measurement {
         &self,
     ) -> io::Result<DelayedMeasurement<SystemCpuLoad>> {
         let measurement = self.cpu_load()?;
-        Ok(DelayedMeasurement::new(
-            Box::new(move || {
-                measurement.done().map(|ls| {
-                    let mut it = ls.iter();
-                    let first = it.next().unwrap().clone(); // has to be a variable, rust moves the iterator otherwise
-                    it.fold(first, |acc, l| acc.avg_add(l))
-                })
-            }),
-            Some(0),
-        ))
+        Ok(measurement.and_then(|ls| {
+            SystemCpuLoad::mean(&ls).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "No cpu load measured")
+            })
+        }))
     }
 
     fn cpu_load_by_pid(
@@ -35,9 +36,23 @@ pub trait Measurement {
         pid: u32,
     ) -> std::io::Result<DelayedMeasurement<f64>>;
 
+    fn load_average(&self) -> io::Result<LoadAverage>;
+
+    fn pressure(&self) -> io::Result<SystemPressure>;
+    fn cgroup_pressure<P: AsRef<path::Path>>(
+        &self,
+        cgroup: P,
+    ) -> io::Result<SystemPressure>;
+
     fn memory(&self) -> std::io::Result<SystemMemory>;
     fn memory_by_pid(&self, pid: u32) -> std::io::Result<(u64, u64)>;
+    fn vmstat(&self) -> io::Result<VmStat>;
+    fn vmstat_rates(&self) -> io::Result<DelayedMeasurement<VmStatRates>>;
     fn swap(&self) -> std::io::Result<SystemSwap>;
+    fn swap_devices(&self) -> io::Result<Vec<SwapDevice>>;
+    fn hugepages(&self) -> io::Result<HugePages>;
+    fn slab_info(&self) -> io::Result<SlabInfo>;
+    fn numa_nodes(&self) -> io::Result<Vec<NumaNode>>;
     fn mounts(&self) -> io::Result<Vec<FileSystem>>;
     fn mount_at<P: AsRef<path::Path>>(
         &self,
@@ -54,9 +69,26 @@ pub trait Measurement {
                 })
         })
     }
+    fn block_device_stats(
+        &self,
+    ) -> io::Result<BTreeMap<String, BlockDeviceStats>>;
+    fn block_device_load(
+        &self,
+    ) -> io::Result<DelayedMeasurement<BTreeMap<String, BlockDeviceLoad>>>;
     fn networks(&self) -> io::Result<BTreeMap<String, Network>>;
+    fn network_links(&self) -> io::Result<BTreeMap<String, NetworkLink>>;
     fn network_stats(&self, interface: &str) -> io::Result<NetworkStats>;
+    fn all_network_stats(&self) -> io::Result<BTreeMap<String, NetworkStats>>;
+    fn network_rates(
+        &self,
+    ) -> io::Result<DelayedMeasurement<BTreeMap<String, NetworkRates>>>;
     fn socket_stats(&self) -> io::Result<SocketStats>;
+    fn protocol_counters(&self) -> io::Result<ProtocolCounters>;
+    fn protocol_rates(&self) -> io::Result<DelayedMeasurement<ProtocolRates>>;
+    fn sockets(&self) -> io::Result<Vec<Socket>>;
+    fn unix_sockets(&self) -> io::Result<Vec<UnixSocket>>;
+    fn listening_ports(&self) -> io::Result<Vec<ListeningPort>>;
+    fn sockets_by_pid(&self, pid: u32) -> io::Result<Vec<Socket>>;
     fn boot_time(&self) -> io::Result<OffsetDateTime>;
     fn process_uptime(&self, pid: u32) -> io::Result<Duration>;
     fn process_pid(&self, cmd: &str) -> io::Result<Vec<usize>>;
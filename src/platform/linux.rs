@@ -1,31 +1,168 @@
-use std::{collections::BTreeMap, io, mem, path, str, time::Duration};
+use std::{
+    collections::BTreeMap,
+    io, mem,
+    net::{IpAddr, SocketAddr},
+    path::{self, Path, PathBuf},
+    str,
+    time::Duration,
+};
 
 use bytesize::ByteSize;
 use libc::{statvfs, sysinfo};
 use nom::{
+    branch::alt,
     bytes::{
-        complete::{tag, take_until},
+        complete::{tag, take_till1, take_until},
         take_till,
     },
-    character::complete::{digit1, multispace0, not_line_ending, space1},
+    character::{
+        self,
+        complete::{
+            digit1, hex_digit1, multispace0, not_line_ending, space0, space1,
+        },
+    },
     combinator::{complete, map, map_res, opt, verify},
     error::ParseError,
-    multi::{fold_many0, many1},
-    sequence::{delimited, preceded},
+    multi::{fold_many0, many0, many1, separated_list0},
+    number::complete::double,
+    sequence::{delimited, preceded, terminated},
     IResult, Parser,
 };
 use time::OffsetDateTime;
 
 use crate::{
-    disk::FileSystem,
+    disk::{BlockDeviceLoad, BlockDeviceStats, FileSystem},
     helper::read_file,
-    network::{Network, NetworkStats, SocketStats},
-    platform::unix,
+    network::{
+        Duplex, InterfaceFlags, InterfaceKind, ListeningPort, Network,
+        NetworkLink, NetworkRates, NetworkStats, OperState, ProtocolCounters,
+        ProtocolRates, Socket, SocketOwner, SocketProtocol, SocketStats,
+        SocketTimer, TcpState, UnixSocket, UnixSocketState,
+    },
+    numa::NumaNode,
+    platform::{sock_diag, unix},
     process::{ProcessInfo, ProcessStatus},
-    saturating_sub_bytes, DelayedMeasurement, Measurement, PlatformMemory,
-    SystemCpuLoad, SystemCpuTime, SystemMemory, SystemSwap,
+    saturating_sub_bytes, CpuSnapshot, DelayedMeasurement, HugePagePool,
+    HugePages, LoadAverage, Measurement, PlatformCpuTime, PlatformMemory,
+    Pressure, PressureStats, SlabCache, SlabInfo, SwapDevice, SystemCpuLoad,
+    SystemCpuTime, SystemMemory, SystemPressure, SystemSwap,
+    TransparentHugePages, VmStat, VmStatRates, ZramStats,
 };
-pub struct MeasurementImpl;
+pub struct MeasurementImpl {
+    root: PathBuf,
+    socket_backend: SocketBackend,
+}
+
+/// Where `sockets()`, `socket_stats()` and `listening_ports()` read the
+/// socket tables from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocketBackend {
+    /// Netlink, falling back to `/proc/net` with a logged warning when the
+    /// dump fails. The default of `MeasurementImpl::new()`
+    Auto,
+    /// `NETLINK_SOCK_DIAG` dumps, which always describe the calling process'
+    /// network namespace. Errors are returned as they are
+    Netlink,
+    /// The tables in `/proc/net` below the root. The default of
+    /// `MeasurementImpl::with_root()`
+    Proc,
+}
+
+impl MeasurementImpl {
+    /// Reads procfs and sysfs below `root` instead of `/`, e.g. a host
+    /// filesystem bind-mounted into a container or a captured fixture tree.
+    ///
+    /// Measurements backed by syscalls rather than files, like `networks()`,
+    /// still describe the calling process' own namespaces. Syscall fallbacks
+    /// for missing files, like `sysinfo` for `memory()`, are only used with
+    /// the default root of `/`. Socket tables are read from `/proc/net`
+    /// below `root`, see `with_socket_backend`.
+    pub fn with_root<P: Into<PathBuf>>(root: P) -> Self {
+        MeasurementImpl {
+            root: root.into(),
+            socket_backend: SocketBackend::Proc,
+        }
+    }
+
+    /// Selects how the socket tables are read, see `SocketBackend`
+    pub fn with_socket_backend(mut self, backend: SocketBackend) -> Self {
+        self.socket_backend = backend;
+        self
+    }
+
+    fn path<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        rooted(&self.root, path)
+    }
+}
+
+fn rooted<P: AsRef<Path>>(root: &Path, path: P) -> PathBuf {
+    let path = path.as_ref();
+    root.join(path.strip_prefix("/").unwrap_or(path))
+}
+
+/// A file tree below the temp dir for `with_root` tests, removed on drop so
+/// a failing assert doesn't leak it
+#[cfg(test)]
+struct Fixture {
+    root: PathBuf,
+}
+
+#[cfg(test)]
+impl Fixture {
+    /// Writes `files` as `(relative path, contents)` pairs, creating parent
+    /// directories. A path ending in `/` creates an empty directory.
+    fn new<P, C, I>(name: &str, files: I) -> Fixture
+    where
+        P: AsRef<str>,
+        C: AsRef<[u8]>,
+        I: IntoIterator<Item = (P, C)>,
+    {
+        let fixture = Fixture::empty(name);
+        for (path, contents) in files {
+            fixture.write(path.as_ref(), contents);
+        }
+        fixture
+    }
+
+    fn empty(name: &str) -> Fixture {
+        let root = std::env::temp_dir()
+            .join(format!("sys-measure-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        Fixture { root }
+    }
+
+    fn write<C: AsRef<[u8]>>(&self, path: &str, contents: C) {
+        if path.ends_with('/') {
+            std::fs::create_dir_all(self.path(path)).unwrap();
+        } else {
+            let path = self.path(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+    }
+
+    fn symlink<P: AsRef<Path>>(&self, target: P, path: &str) {
+        let path = self.path(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::os::unix::fs::symlink(target, path).unwrap();
+    }
+
+    fn path(&self, path: &str) -> PathBuf {
+        rooted(&self.root, path)
+    }
+
+    fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+#[cfg(test)]
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
 
 impl From<&str> for ProcessStatus {
     fn from(status: &str) -> ProcessStatus {
@@ -46,15 +183,15 @@ impl From<&str> for ProcessStatus {
     }
 }
 
-fn value_from_file<T: str::FromStr>(path: &str) -> io::Result<T> {
+fn value_from_file<T: str::FromStr>(path: &Path) -> io::Result<T> {
     read_file(path)?
         .trim_end_matches('\n')
         .parse()
         .map_err(|_| {
-            io::Error::new(
-                io::ErrorKind::Other,
-                format!("File: \"{}\" doesn't contain an int value", &path),
-            )
+            io::Error::other(format!(
+                "File: \"{}\" doesn't contain an int value",
+                path.display()
+            ))
         })
 }
 
@@ -94,37 +231,69 @@ fn word_s(input: &str) -> IResult<&str, &str> {
     take_till(|c| is_space(c as u8)).parse(input)
 }
 
-fn proc_stat_cpu_time(input: &str) -> IResult<&str, SystemCpuTime> {
+fn proc_stat_cpu_columns(input: &str) -> IResult<&str, SystemCpuTime> {
     map(
-        preceded(ws(proc_stat_cpu_prefix), (num, num, num, num, num, num)),
-        |(user, nice, system, idle, iowait, irq)| SystemCpuTime {
-            user,
-            nice,
-            system,
-            idle,
-            interrupt: irq,
-            other: iowait,
+        (
+            (num, num, num, num),
+            // older kernels report fewer columns
+            (opt(num), opt(num), opt(num), opt(num), opt(num), opt(num)),
+        ),
+        |(
+            (user, nice, system, idle),
+            (iowait, irq, softirq, steal, guest, guest_nice),
+        )| {
+            let platform = PlatformCpuTime {
+                iowait: iowait.unwrap_or(0),
+                irq: irq.unwrap_or(0),
+                softirq: softirq.unwrap_or(0),
+                steal: steal.unwrap_or(0),
+                guest: guest.unwrap_or(0),
+                guest_nice: guest_nice.unwrap_or(0),
+            };
+            SystemCpuTime {
+                user,
+                nice,
+                system,
+                idle,
+                interrupt: platform.irq + platform.softirq,
+                other: platform.iowait + platform.steal,
+                platform,
+            }
         },
     )
     .parse(input)
 }
 
-fn proc_stat_cpu_times(input: &str) -> IResult<&str, Vec<SystemCpuTime>> {
-    preceded(
-        map(ws(not_line_ending), proc_stat_cpu_aggregate),
+fn proc_stat_cpu_time(input: &str) -> IResult<&str, SystemCpuTime> {
+    preceded(ws(proc_stat_cpu_prefix), proc_stat_cpu_columns).parse(input)
+}
+
+fn proc_stat_cpu_total(input: &str) -> IResult<&str, SystemCpuTime> {
+    preceded(ws(proc_stat_cpu_aggregate), proc_stat_cpu_columns).parse(input)
+}
+
+fn proc_stat_cpu_times(
+    input: &str,
+) -> IResult<&str, (SystemCpuTime, Vec<SystemCpuTime>)> {
+    (
+        map_res(ws(not_line_ending), |input| {
+            proc_stat_cpu_total(input)
+                .map(|(_, res)| res)
+                .map_err(|_| ())
+        }),
         many1(map_res(ws(not_line_ending), |input| {
             proc_stat_cpu_time(input)
                 .map(|(_, res)| res)
                 .map_err(|_| ())
         })),
     )
-    .parse(input)
+        .parse(input)
 }
 
-fn cpu_time() -> io::Result<Vec<SystemCpuTime>> {
-    read_file("/proc/stat").and_then(|data| {
+fn cpu_time(root: &Path) -> io::Result<CpuSnapshot> {
+    read_file(rooted(root, "/proc/stat")).and_then(|data| {
         proc_stat_cpu_times(&data)
-            .map(|(_, res)| res)
+            .map(|(_, (total, times))| CpuSnapshot::new(total, times))
             .map_err(|err| {
                 io::Error::new(io::ErrorKind::InvalidData, err.to_string())
             })
@@ -145,10 +314,27 @@ processes 157384
 procs_running 6
 procs_blocked 0
 softirq 191683246 1 74513529 13 24377461 2137122 0 199 78364024 64545 12226352";
-    let result = proc_stat_cpu_times(input).unwrap().1;
+    let (total, result) = proc_stat_cpu_times(input).unwrap().1;
+    assert_eq!(total.user, 571797);
+    assert_eq!(total.platform.steal, 2218);
     assert_eq!(result.len(), 4);
     assert_eq!(result[0].user, 139405);
-    assert_eq!(result[0].nice, 9696)
+    assert_eq!(result[0].nice, 9696);
+    assert_eq!(result[0].platform.iowait, 42717);
+    assert_eq!(result[0].platform.softirq, 5108);
+    assert_eq!(result[0].platform.steal, 503);
+    assert_eq!(result[0].interrupt, 5108);
+    assert_eq!(result[0].other, 42717 + 503);
+
+    let old_kernel =
+        proc_stat_cpu_times("cpu  10 20 30 40\ncpu0 10 20 30 40\n")
+            .unwrap()
+            .1
+             .1;
+    assert_eq!(old_kernel[0].idle, 40);
+    assert_eq!(old_kernel[0].other, 0);
+
+    assert!(proc_stat_cpu_times("cpu0 10 20 30 40\n").is_err());
 }
 
 fn proc_meminfo_line(input: &str) -> IResult<&str, (&str, ByteSize)> {
@@ -186,8 +372,8 @@ fn proc_meminfo(input: &str) -> IResult<&str, BTreeMap<String, ByteSize>> {
     .parse(input)
 }
 
-fn memory_stats() -> io::Result<BTreeMap<String, ByteSize>> {
-    read_file("/proc/meminfo").and_then(|data| {
+fn memory_stats(root: &Path) -> io::Result<BTreeMap<String, ByteSize>> {
+    read_file(rooted(root, "/proc/meminfo")).and_then(|data| {
         proc_meminfo(&data).map(|(_, res)| res).map_err(|err| {
             io::Error::new(io::ErrorKind::InvalidData, err.to_string())
         })
@@ -250,14 +436,749 @@ DirectMap1G:    13631488 kB
 ";
     let result = proc_meminfo(input).unwrap().1;
     assert_eq!(result.len(), 47);
-    assert_eq!(
-        result.get(&"Buffers".to_string()),
-        Some(&ByteSize::kib(4724))
+    assert_eq!(result.get("Buffers"), Some(&ByteSize::kib(4724)));
+    assert_eq!(result.get("KReclaimable"), Some(&ByteSize::kib(427080)));
+}
+
+fn proc_loadavg(input: &str) -> IResult<&str, LoadAverage> {
+    map(
+        (
+            ws(double),
+            ws(double),
+            ws(double),
+            (num, preceded(tag("/"), num)),
+            num,
+        ),
+        |(one, five, fifteen, (runnable, total), last_pid)| LoadAverage {
+            one,
+            five,
+            fifteen,
+            runnable: Some(runnable),
+            total: Some(total),
+            last_pid: Some(last_pid),
+        },
+    )
+    .parse(input)
+}
+
+#[test]
+fn test_proc_loadavg() {
+    let result = proc_loadavg("0.20 0.18 0.12 1/80 11206\n").unwrap().1;
+    assert_eq!(result.one, 0.20);
+    assert_eq!(result.five, 0.18);
+    assert_eq!(result.fifteen, 0.12);
+    assert_eq!(result.runnable, Some(1));
+    assert_eq!(result.total, Some(80));
+    assert_eq!(result.last_pid, Some(11206));
+}
+
+fn proc_pressure_line(input: &str) -> IResult<&str, (&str, PressureStats)> {
+    map(
+        (
+            ws(alt((tag("some"), tag("full")))),
+            preceded(tag("avg10="), double),
+            preceded(ws(tag("avg60=")), double),
+            preceded(ws(tag("avg300=")), double),
+            preceded(ws(tag("total=")), num),
+        ),
+        |(kind, avg10, avg60, avg300, total)| {
+            (
+                kind,
+                PressureStats {
+                    avg10: avg10 as f32,
+                    avg60: avg60 as f32,
+                    avg300: avg300 as f32,
+                    total: Duration::from_micros(total),
+                },
+            )
+        },
+    )
+    .parse(input)
+}
+
+fn proc_pressure(input: &str) -> IResult<&str, Pressure> {
+    fold_many0(
+        map_res(
+            verify(ws(not_line_ending), |item: &str| !item.is_empty()),
+            |input| {
+                proc_pressure_line(input)
+                    .map(|(_, res)| res)
+                    .map_err(|_| ())
+            },
+        ),
+        Pressure::default,
+        |mut pressure: Pressure, (kind, stats)| {
+            match kind {
+                "some" => pressure.some = Some(stats),
+                _ => pressure.full = Some(stats),
+            }
+            pressure
+        },
+    )
+    .parse(input)
+}
+
+fn read_pressure(path: &Path) -> io::Result<Pressure> {
+    read_file(path).and_then(|data| {
+        proc_pressure(&data).map(|(_, res)| res).map_err(|err| {
+            io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+        })
+    })
+}
+
+/// Reads the PSI files of every resource, `file` maps a resource name to the
+/// file that holds its pressure.
+fn system_pressure<F: Fn(&str) -> PathBuf>(
+    file: F,
+) -> io::Result<SystemPressure> {
+    Ok(SystemPressure {
+        cpu: read_pressure(&file("cpu"))?,
+        memory: read_pressure(&file("memory"))?,
+        io: read_pressure(&file("io"))?,
+        irq: read_pressure(&file("irq")).ok(),
+    })
+}
+
+#[test]
+fn test_proc_pressure() {
+    let memory = proc_pressure(
+        "some avg10=0.00 avg60=0.02 avg300=0.09 total=2958321
+full avg10=1.50 avg60=0.02 avg300=0.06 total=2476082
+",
+    )
+    .unwrap()
+    .1;
+    let some = memory.some.unwrap();
+    assert_eq!(some.avg60, 0.02);
+    assert_eq!(some.avg300, 0.09);
+    assert_eq!(some.total, Duration::from_micros(2958321));
+    let full = memory.full.unwrap();
+    assert_eq!(full.avg10, 1.5);
+    assert_eq!(full.total, Duration::from_micros(2476082));
+
+    let irq = proc_pressure("full avg10=0.00 avg60=0.00 avg300=0.00 total=0\n")
+        .unwrap()
+        .1;
+    assert!(irq.some.is_none());
+    assert_eq!(irq.full, Some(PressureStats::default()));
+}
+
+fn proc_vmstat_line(input: &str) -> IResult<&str, (&str, u64)> {
+    complete((ws(word_s), num)).parse(input)
+}
+
+fn proc_vmstat(input: &str) -> IResult<&str, BTreeMap<String, u64>> {
+    fold_many0(
+        map_res(
+            verify(ws(not_line_ending), |item: &str| !item.is_empty()),
+            |input| {
+                opt(proc_vmstat_line)
+                    .parse(input)
+                    .map(|(_, res)| res)
+                    .map_err(|_| ())
+            },
+        ),
+        BTreeMap::new,
+        |mut map: BTreeMap<String, u64>, opt| {
+            if let Some((key, val)) = opt {
+                map.insert(key.to_string(), val);
+            }
+            map
+        },
+    )
+    .parse(input)
+}
+
+fn vmstat(root: &Path) -> io::Result<VmStat> {
+    read_file(rooted(root, "/proc/vmstat")).and_then(|data| {
+        proc_vmstat(&data)
+            .map(|(_, counters)| VmStat { counters })
+            .map_err(|err| {
+                io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+            })
+    })
+}
+
+#[test]
+fn test_proc_vmstat() {
+    let input = "nr_free_pages 842823
+nr_zone_inactive_anon 47521
+pgfault 91234567
+pgmajfault 1234
+pswpin 0
+pswpout 12
+pgscan_kswapd 5000
+pgscan_direct 100
+oom_kill 2
+";
+    let result = proc_vmstat(input).unwrap().1;
+    assert_eq!(result.len(), 9);
+    assert_eq!(result.get("pgfault"), Some(&91234567));
+    assert_eq!(result.get("oom_kill"), Some(&2));
+    let vmstat = VmStat { counters: result };
+    assert_eq!(vmstat.pgscan(), 5100);
+    assert_eq!(vmstat.get("pgsteal_kswapd"), 0);
+}
+
+/// Undoes the octal escapes the kernel writes for whitespace and backslashes
+/// in paths, e.g. `\040` for a space
+fn unescape_octal(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 4)
+            .filter(|digits| digits.iter().all(|d| (b'0'..=b'7').contains(d)))
+            .and_then(|digits| str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+        match (bytes[i], escaped) {
+            (b'\\', Some(byte)) => {
+                out.push(byte);
+                i += 4;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Picks the selected value out of a sysfs choice list such as
+/// `[lzo-rle] lzo lz4`
+fn bracketed_selection(input: &str) -> Option<&str> {
+    input
+        .split_whitespace()
+        .find_map(|item| item.strip_prefix('[')?.strip_suffix(']'))
+}
+
+fn proc_swaps_line(input: &str) -> IResult<&str, SwapDevice> {
+    map(
+        complete((
+            ws(word_s),
+            ws(word_s),
+            num,
+            num,
+            ws(character::complete::i32),
+        )),
+        |(name, kind, size, used, priority)| SwapDevice {
+            name: unescape_octal(name),
+            kind: kind.to_string(),
+            size: ByteSize::kib(size),
+            used: ByteSize::kib(used),
+            priority,
+            zram: None,
+        },
+    )
+    .parse(input)
+}
+
+fn proc_swaps(input: &str) -> IResult<&str, Vec<SwapDevice>> {
+    preceded(
+        (tag("Filename"), not_line_ending),
+        many0(map_res(
+            verify(ws(not_line_ending), |item: &str| !item.is_empty()),
+            |input| proc_swaps_line(input).map(|(_, res)| res).map_err(|_| ()),
+        )),
+    )
+    .parse(input)
+}
+
+fn sys_zram_mm_stat(input: &str) -> IResult<&str, ZramStats> {
+    map(
+        verify(many1(num::<u64>), |values: &Vec<u64>| values.len() >= 7),
+        |values| ZramStats {
+            orig_data_size: ByteSize::b(values[0]),
+            compr_data_size: ByteSize::b(values[1]),
+            mem_used_total: ByteSize::b(values[2]),
+            mem_limit: ByteSize::b(values[3]),
+            mem_used_max: ByteSize::b(values[4]),
+            same_pages: values[5],
+            pages_compacted: values[6],
+            huge_pages: values.get(7).copied(),
+            algorithm: None,
+        },
+    )
+    .parse(input)
+}
+
+fn zram_stats(dir: &Path) -> io::Result<ZramStats> {
+    let data = read_file(dir.join("mm_stat"))?;
+    let mut stats =
+        sys_zram_mm_stat(&data).map(|(_, res)| res).map_err(|err| {
+            io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+        })?;
+    stats.algorithm = read_file(dir.join("comp_algorithm"))
+        .ok()
+        .and_then(|data| bracketed_selection(&data).map(str::to_string));
+    Ok(stats)
+}
+
+fn swap_devices(root: &Path) -> io::Result<Vec<SwapDevice>> {
+    let data = read_file(rooted(root, "/proc/swaps"))?;
+    let mut devices = proc_swaps(&data).map(|(_, res)| res).map_err(|err| {
+        io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+    })?;
+    for device in devices.iter_mut() {
+        if let Some(zram) = device
+            .name
+            .strip_prefix("/dev/")
+            .filter(|name| name.starts_with("zram"))
+        {
+            device.zram =
+                zram_stats(&rooted(root, format!("/sys/block/{zram}"))).ok();
+        }
+    }
+    Ok(devices)
+}
+
+#[test]
+fn test_proc_swaps() {
+    let input = "Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority
+/dev/zram0                              partition\t8388604\t\t1048576\t\t100
+/swap\\040file                          file\t\t2097148\t\t0\t\t-2
+";
+    let devices = proc_swaps(input).unwrap().1;
+    assert_eq!(devices.len(), 2);
+    assert_eq!(devices[0].name, "/dev/zram0");
+    assert_eq!(devices[0].kind, "partition");
+    assert_eq!(devices[0].size, ByteSize::kib(8388604));
+    assert_eq!(devices[0].used, ByteSize::kib(1048576));
+    assert_eq!(devices[0].priority, 100);
+    assert_eq!(devices[1].name, "/swap file");
+    assert_eq!(devices[1].kind, "file");
+    assert_eq!(devices[1].priority, -2);
+
+    let empty = "Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority\n";
+    assert!(proc_swaps(empty).unwrap().1.is_empty());
+}
+
+#[test]
+fn test_sys_zram_mm_stat() {
+    let input = "  4096000   1024000   1200000        0   1300000      120       15       8        0\n";
+    let stats = sys_zram_mm_stat(input).unwrap().1;
+    assert_eq!(stats.orig_data_size, ByteSize::b(4096000));
+    assert_eq!(stats.mem_used_total, ByteSize::b(1200000));
+    assert_eq!(stats.same_pages, 120);
+    assert_eq!(stats.huge_pages, Some(8));
+    assert!((stats.compression_ratio().unwrap() - 3.413).abs() < 0.001);
+
+    let old_kernel = sys_zram_mm_stat("4096 1024 2048 0 2048 0 0\n").unwrap().1;
+    assert_eq!(old_kernel.huge_pages, None);
+    assert!(sys_zram_mm_stat("0 0 0\n").is_err());
+
+    assert_eq!(bracketed_selection("lzo [lzo-rle] lz4\n"), Some("lzo-rle"));
+    assert_eq!(bracketed_selection("zstd\n"), None);
+}
+
+fn hugepage_pool(dir: &Path, page_size: ByteSize) -> io::Result<HugePagePool> {
+    let count = |name: &str| value_from_file::<u64>(&dir.join(name));
+    Ok(HugePagePool {
+        page_size,
+        total: count("nr_hugepages")?,
+        free: count("free_hugepages")?,
+        reserved: count("resv_hugepages")?,
+        surplus: count("surplus_hugepages")?,
+    })
+}
+
+fn hugepage_pools(root: &Path) -> io::Result<Vec<HugePagePool>> {
+    let dir = match std::fs::read_dir(rooted(root, "/sys/kernel/mm/hugepages"))
+    {
+        Ok(dir) => dir,
+        // Kernel built without hugetlbfs
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Ok(Vec::new())
+        }
+        Err(err) => return Err(err),
+    };
+
+    let mut pools = Vec::new();
+    for entry in dir {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(Ok(kib)) = name
+            .strip_prefix("hugepages-")
+            .and_then(|size| size.strip_suffix("kB"))
+            .map(str::parse::<u64>)
+        {
+            pools.push(hugepage_pool(&entry.path(), ByteSize::kib(kib))?);
+        }
+    }
+    pools.sort_by_key(|pool| pool.page_size);
+    Ok(pools)
+}
+
+fn transparent_hugepages(
+    root: &Path,
+    meminfo: &BTreeMap<String, ByteSize>,
+) -> TransparentHugePages {
+    let dir = rooted(root, "/sys/kernel/mm/transparent_hugepage");
+    let setting = |name: &str| {
+        read_file(dir.join(name))
+            .ok()
+            .and_then(|data| bracketed_selection(&data).map(str::to_string))
+    };
+    let get = |key: &str| meminfo.get(key).copied().unwrap_or(ByteSize::b(0));
+
+    TransparentHugePages {
+        enabled: setting("enabled"),
+        defrag: setting("defrag"),
+        shmem_enabled: setting("shmem_enabled"),
+        pmd_size: value_from_file(&dir.join("hpage_pmd_size"))
+            .ok()
+            .map(ByteSize::b),
+        anon: get("AnonHugePages"),
+        shmem: get("ShmemHugePages"),
+        file: get("FileHugePages"),
+    }
+}
+
+#[test]
+fn test_hugepages() {
+    let fixture = Fixture::new(
+        "hugepages",
+        [
+            (
+                "sys/kernel/mm/transparent_hugepage/enabled",
+                "always [madvise] never\n",
+            ),
+            (
+                "sys/kernel/mm/transparent_hugepage/hpage_pmd_size",
+                "2097152\n",
+            ),
+        ],
+    );
+    for (size, counts) in [
+        ("hugepages-2048kB", ["512", "500", "4", "0"]),
+        ("hugepages-1048576kB", ["2", "2", "0", "0"]),
+    ] {
+        for (name, count) in [
+            "nr_hugepages", "free_hugepages", "resv_hugepages",
+            "surplus_hugepages",
+        ]
+        .iter()
+        .zip(counts)
+        {
+            fixture.write(
+                &format!("sys/kernel/mm/hugepages/{size}/{name}"),
+                format!("{count}\n"),
+            );
+        }
+    }
+    let root = fixture.root().to_path_buf();
+
+    let pools = hugepage_pools(&root).unwrap();
+    assert_eq!(pools.len(), 2);
+    assert_eq!(pools[0].page_size, ByteSize::mib(2));
+    assert_eq!(pools[0].total, 512);
+    assert_eq!(pools[0].used(), 16);
+    assert_eq!(pools[1].total_bytes(), ByteSize::gib(2));
+
+    let meminfo =
+        BTreeMap::from([("AnonHugePages".to_string(), ByteSize::kib(4096))]);
+    let transparent = transparent_hugepages(&root, &meminfo);
+    assert_eq!(transparent.enabled.as_deref(), Some("madvise"));
+    assert_eq!(transparent.defrag, None);
+    assert_eq!(transparent.pmd_size, Some(ByteSize::mib(2)));
+    assert_eq!(transparent.anon, ByteSize::kib(4096));
+    assert_eq!(transparent.file, ByteSize::b(0));
+
+    drop(fixture);
+    assert!(hugepage_pools(&root).unwrap().is_empty());
+}
+
+fn page_size() -> u64 {
+    unsafe { libc::sysconf(libc::_SC_PAGESIZE) as u64 }
+}
+
+fn proc_slabinfo_line(input: &str) -> IResult<&str, SlabCache> {
+    map(
+        complete((
+            ws(word_s),
+            (num, num, num, num, num),
+            preceded(
+                (ws(tag(":")), ws(tag("tunables"))),
+                (num::<u64>, num::<u64>, num::<u64>),
+            ),
+            preceded(
+                (ws(tag(":")), ws(tag("slabdata"))),
+                (num, num, num::<u64>),
+            ),
+        )),
+        |(
+            name,
+            (active_objs, num_objs, obj_size, objs_per_slab, pages_per_slab),
+            _,
+            (active_slabs, num_slabs, _),
+        )| SlabCache {
+            name: name.to_string(),
+            active_objs,
+            num_objs,
+            obj_size: ByteSize::b(obj_size),
+            objs_per_slab,
+            pages_per_slab,
+            active_slabs,
+            num_slabs,
+            size: ByteSize::b(0),
+        },
+    )
+    .parse(input)
+}
+
+fn proc_slabinfo(input: &str) -> IResult<&str, Vec<SlabCache>> {
+    preceded(
+        (tag("slabinfo - version: 2."), not_line_ending),
+        fold_many0(
+            map_res(
+                verify(ws(not_line_ending), |item: &str| !item.is_empty()),
+                |input| {
+                    opt(proc_slabinfo_line)
+                        .parse(input)
+                        .map(|(_, res)| res)
+                        .map_err(|_| ())
+                },
+            ),
+            Vec::new,
+            |mut caches: Vec<SlabCache>, opt| {
+                caches.extend(opt);
+                caches
+            },
+        ),
+    )
+    .parse(input)
+}
+
+/// Reads a SLUB cache from its `/sys/kernel/slab/<name>` directory, where
+/// `objects`, `total_objects` and `slabs` start with the total followed by
+/// per-node counts
+fn sys_slab_cache(name: String, dir: &Path) -> io::Result<SlabCache> {
+    let count = |file: &str| -> io::Result<u64> {
+        read_file(dir.join(file))?
+            .split_whitespace()
+            .next()
+            .and_then(|total| total.parse().ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "{}/{file} doesn't start with a count",
+                        dir.display()
+                    ),
+                )
+            })
+    };
+    let slabs = count("slabs")?;
+    Ok(SlabCache {
+        name,
+        active_objs: count("objects")?,
+        num_objs: count("total_objects")?,
+        obj_size: ByteSize::b(count("slab_size")?),
+        objs_per_slab: count("objs_per_slab")?,
+        pages_per_slab: 1 << count("order")?,
+        active_slabs: slabs,
+        num_slabs: slabs,
+        size: ByteSize::b(0),
+    })
+}
+
+fn sys_slab(root: &Path) -> io::Result<Vec<SlabCache>> {
+    let mut entries = std::fs::read_dir(rooted(root, "/sys/kernel/slab"))?
+        .map(|entry| {
+            entry.map(|entry| {
+                (
+                    entry.file_name().to_string_lossy().to_string(),
+                    entry.path(),
+                )
+            })
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    // Directory order is arbitrary, sort so merged caches get a stable name
+    entries.sort();
+
+    let mut seen = std::collections::BTreeSet::new();
+    let mut caches = Vec::new();
+    for (name, path) in entries {
+        // Merged caches are `:<id>` directories with a symlink per name,
+        // count each of them once under its alphabetically first name
+        if name.starts_with(':') || !seen.insert(std::fs::canonicalize(&path)?)
+        {
+            continue;
+        }
+        caches.push(sys_slab_cache(name, &path)?);
+    }
+    Ok(caches)
+}
+
+fn slab_info(root: &Path) -> io::Result<SlabInfo> {
+    // /proc/slabinfo is only readable by root
+    let mut caches = match read_file(rooted(root, "/proc/slabinfo")) {
+        Ok(data) => {
+            proc_slabinfo(&data).map(|(_, res)| res).map_err(|err| {
+                io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+            })?
+        }
+        Err(_) => sys_slab(root)?,
+    };
+    let page_size = page_size();
+    for cache in caches.iter_mut() {
+        cache.size =
+            ByteSize::b(cache.num_slabs * cache.pages_per_slab * page_size);
+    }
+    Ok(SlabInfo { caches })
+}
+
+#[test]
+fn test_proc_slabinfo() {
+    let input = "slabinfo - version: 2.1
+# name            <active_objs> <num_objs> <objsize> <objperslab> <pagesperslab> : tunables <limit> <batchcount> <sharedfactor> : slabdata <active_slabs> <num_slabs> <sharedavail>
+ext4_groupinfo_4k   2054   2054    152   26    1 : tunables    0    0    0 : slabdata     79     79      0
+fscrypt_inode_info      0      0    120   34    1 : tunables    0    0    0 : slabdata      0      0      0
+AF_VSOCK              12     12   1280   12    4 : tunables    0    0    0 : slabdata      1      1      0
+dentry            160521 163002    192   21    1 : tunables    0    0    0 : slabdata   7762   7762      0
+";
+    let caches = proc_slabinfo(input).unwrap().1;
+    assert_eq!(caches.len(), 4);
+    assert_eq!(caches[2].name, "AF_VSOCK");
+    assert_eq!(caches[2].obj_size, ByteSize::b(1280));
+    assert_eq!(caches[2].pages_per_slab, 4);
+    assert_eq!(caches[3].active_objs, 160521);
+    assert_eq!(caches[3].num_slabs, 7762);
+
+    assert!(proc_slabinfo("slabinfo - version: 1.1\n").is_err());
+}
+
+#[test]
+fn test_sys_slab() {
+    let fixture = Fixture::new(
+        "slab",
+        [
+            ("objects", "160521 N0=160521"),
+            ("total_objects", "163002 N0=163002"),
+            ("slab_size", "192"),
+            ("objs_per_slab", "21"),
+            ("order", "1"),
+            ("slabs", "7762 N0=7762"),
+        ]
+        .map(|(file, value)| {
+            (
+                format!("sys/kernel/slab/:0000192/{file}"),
+                format!("{value}\n"),
+            )
+        }),
     );
+    let merged = fixture.path("sys/kernel/slab/:0000192");
+    fixture.symlink(&merged, "sys/kernel/slab/dentry_alias");
+    fixture.symlink(&merged, "sys/kernel/slab/dentry");
+    fixture.symlink(&merged, "sys/kernel/slab/ext4_alias");
+
+    let info = slab_info(fixture.root()).unwrap();
+    assert_eq!(info.caches.len(), 1);
+    let dentry = &info.caches[0];
+    assert_eq!(dentry.name, "dentry");
+    assert_eq!(dentry.active_objs, 160521);
+    assert_eq!(dentry.num_objs, 163002);
+    assert_eq!(dentry.pages_per_slab, 2);
+    assert_eq!(dentry.size, ByteSize::b(7762 * 2 * page_size()));
+    assert_eq!(info.top_n(5).len(), 1);
+    assert_eq!(info.total(), dentry.size);
+}
+
+fn node_meminfo_line(input: &str) -> IResult<&str, (&str, ByteSize)> {
+    preceded((ws(tag("Node")), ws(digit1)), proc_meminfo_line).parse(input)
+}
+
+fn node_meminfo(input: &str) -> IResult<&str, BTreeMap<String, ByteSize>> {
+    fold_many0(
+        map_res(
+            verify(ws(not_line_ending), |item: &str| !item.is_empty()),
+            |input| {
+                opt(node_meminfo_line)
+                    .parse(input)
+                    .map(|(_, res)| res)
+                    .map_err(|_| ())
+            },
+        ),
+        BTreeMap::new,
+        |mut map: BTreeMap<String, ByteSize>, opt| {
+            if let Some((key, val)) = opt {
+                map.insert(key.to_string(), val);
+            }
+            map
+        },
+    )
+    .parse(input)
+}
+
+/// Parses a cpu list such as `0-3,8-11`, empty for memory-only nodes
+fn cpulist(input: &str) -> IResult<&str, Vec<usize>> {
+    map(
+        separated_list0(tag(","), (num, opt(preceded(tag("-"), num)))),
+        |ranges: Vec<(usize, Option<usize>)>| {
+            ranges
+                .into_iter()
+                .flat_map(|(start, end)| start..=end.unwrap_or(start))
+                .collect()
+        },
+    )
+    .parse(input)
+}
+
+fn numa_node(id: usize, dir: &Path) -> io::Result<NumaNode> {
+    let invalid_data = |err: nom::Err<nom::error::Error<&str>>| -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+    };
+
+    let meminfo_data = read_file(dir.join("meminfo"))?;
+    let meminfo = node_meminfo(&meminfo_data).map_err(invalid_data)?.1;
+    let cpulist_data = read_file(dir.join("cpulist"))?;
+    let cpus = cpulist(&cpulist_data).map_err(invalid_data)?.1;
+    let numastat_data = read_file(dir.join("numastat"))?;
+    let numastat = proc_vmstat(&numastat_data).map_err(invalid_data)?.1;
+
+    let get = |key: &str| meminfo.get(key).copied().unwrap_or(ByteSize::b(0));
+    let total = get("MemTotal");
+    let free = get("MemFree");
+    Ok(NumaNode {
+        id,
+        total,
+        free,
+        used: meminfo
+            .get("MemUsed")
+            .copied()
+            .unwrap_or_else(|| saturating_sub_bytes(total, free)),
+        cpus,
+        meminfo,
+        numastat,
+    })
+}
+
+#[test]
+fn test_node_meminfo() {
+    let input = "Node 1 MemTotal:       32817500 kB
+Node 1 MemFree:        10293044 kB
+Node 1 MemUsed:        22524456 kB
+Node 1 Active:          9001648 kB
+Node 1 HugePages_Total:     0
+Node 1 HugePages_Free:      0
+";
+    let result = node_meminfo(input).unwrap().1;
+    assert_eq!(result.len(), 4);
+    assert_eq!(result.get("MemTotal"), Some(&ByteSize::kib(32817500)));
+    assert_eq!(result.get("MemUsed"), Some(&ByteSize::kib(22524456)));
+}
+
+#[test]
+fn test_cpulist() {
+    assert_eq!(cpulist("0\n").unwrap().1, vec![0]);
     assert_eq!(
-        result.get(&"KReclaimable".to_string()),
-        Some(&ByteSize::kib(427080))
+        cpulist("0-3,8-11\n").unwrap().1,
+        vec![0, 1, 2, 3, 8, 9, 10, 11]
     );
+    assert_eq!(cpulist("1,4-5,7").unwrap().1, vec![1, 4, 5, 7]);
+    assert!(cpulist("\n").unwrap().1.is_empty());
 }
 
 fn get_process_cpu_time(input: &str) -> io::Result<(u64, u64)> {
@@ -278,8 +1199,8 @@ fn get_process_cpu_time(input: &str) -> io::Result<(u64, u64)> {
     ))
 }
 
-fn proc_cpu_time(pid: u32) -> io::Result<(u64, u64)> {
-    read_file(format!("/proc/{pid}/stat").as_str())
+fn proc_cpu_time(root: &Path, pid: u32) -> io::Result<(u64, u64)> {
+    read_file(rooted(root, format!("/proc/{pid}/stat")))
         .and_then(|op| get_process_cpu_time(&op))
 }
 
@@ -349,11 +1270,11 @@ fn get_process_status(input: &str) -> io::Result<ProcessInfo> {
             _ => {}
         }
     }
-    return Ok(retval);
+    Ok(retval)
 }
 
-fn proc_status(pid: u32) -> io::Result<ProcessInfo> {
-    read_file(format!("/proc/{pid}/status").as_str())
+fn proc_status(root: &Path, pid: u32) -> io::Result<ProcessInfo> {
+    read_file(rooted(root, format!("/proc/{pid}/status")))
         .and_then(|op| get_process_status(&op))
 }
 
@@ -472,6 +1393,133 @@ tmpfs /sys/fs/cgroup tmpfs rw,nosuid,nodev,noexec,relatime,mode=755,inode64 0 0"
     assert!(root.fstype == "btrfs");
 }
 
+fn proc_diskstats_line(input: &str) -> IResult<&str, BlockDeviceStats> {
+    map(
+        (
+            num,
+            num,
+            ws(word_s),
+            (num, num, num, num, num, num, num, num, num, num, num),
+            // discard columns, Linux 4.18+
+            opt((num, num, num, num)),
+            // flush columns, Linux 5.5+
+            opt((num, num)),
+        ),
+        |(
+            major,
+            minor,
+            name,
+            (
+                read_ios,
+                read_merges,
+                read_sectors,
+                read_ticks,
+                write_ios,
+                write_merges,
+                write_sectors,
+                write_ticks,
+                in_flight,
+                io_ticks,
+                time_in_queue,
+            ),
+            discard,
+            flush,
+        )| {
+            let (discard_ios, discard_merges, discard_sectors, discard_ticks) =
+                discard.unwrap_or_default();
+            let (flush_ios, flush_ticks) = flush.unwrap_or_default();
+            BlockDeviceStats {
+                major,
+                minor,
+                name: name.to_string(),
+                read_ios,
+                read_merges,
+                read_sectors,
+                read_ticks,
+                write_ios,
+                write_merges,
+                write_sectors,
+                write_ticks,
+                in_flight,
+                io_ticks,
+                time_in_queue,
+                discard_ios,
+                discard_merges,
+                discard_sectors,
+                discard_ticks,
+                flush_ios,
+                flush_ticks,
+            }
+        },
+    )
+    .parse(input)
+}
+
+fn proc_diskstats(
+    input: &str,
+) -> IResult<&str, BTreeMap<String, BlockDeviceStats>> {
+    fold_many0(
+        map_res(
+            verify(ws(not_line_ending), |item: &str| !item.is_empty()),
+            |input| {
+                proc_diskstats_line(input)
+                    .map(|(_, res)| res)
+                    .map_err(|_| ())
+            },
+        ),
+        BTreeMap::new,
+        |mut map: BTreeMap<String, BlockDeviceStats>, stats| {
+            map.insert(stats.name.clone(), stats);
+            map
+        },
+    )
+    .parse(input)
+}
+
+fn block_device_stats(
+    root: &Path,
+) -> io::Result<BTreeMap<String, BlockDeviceStats>> {
+    read_file(rooted(root, "/proc/diskstats")).and_then(|data| {
+        proc_diskstats(&data).map(|(_, res)| res).map_err(|err| {
+            io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+        })
+    })
+}
+
+#[test]
+fn test_proc_diskstats() {
+    let input = "   8       0 sda 1123 22 45678 901 2345 67 89012 3456 0 4567 8901
+   8       1 sda1 100 0 2000 30 40 0 500 60 0 70 90 1 0 8 2
+ 259       0 nvme0n1 183364 58149 11470418 37012 1142785 614325 39093256 1208046 0 708152 1262290 31290 0 115233936 4150 71394 13081
+   7       0 loop0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+";
+    let result = proc_diskstats(input).unwrap().1;
+    assert_eq!(result.len(), 4);
+
+    let sda = result.get("sda").unwrap();
+    assert_eq!(sda.major, 8);
+    assert_eq!(sda.read_sectors, 45678);
+    assert_eq!(sda.time_in_queue, 8901);
+    assert_eq!(sda.discard_ios, 0);
+    assert_eq!(sda.flush_ios, 0);
+
+    let sda1 = result.get("sda1").unwrap();
+    assert_eq!(sda1.minor, 1);
+    assert_eq!(sda1.discard_ios, 1);
+    assert_eq!(sda1.discard_sectors, 8);
+    assert_eq!(sda1.flush_ios, 0);
+
+    let nvme = result.get("nvme0n1").unwrap();
+    assert_eq!(nvme.write_ios, 1142785);
+    assert_eq!(nvme.discard_sectors, 115233936);
+    assert_eq!(nvme.discard_ticks, 4150);
+    assert_eq!(nvme.flush_ios, 71394);
+    assert_eq!(nvme.flush_ticks, 13081);
+}
+
+// Only the orphan count isn't available from the socket listings, the rest
+// is kept to check those against
+#[allow(dead_code)]
 #[derive(Debug, Default)]
 struct ProcNetSockStat {
     tcp_in_use: usize,
@@ -480,7 +1528,6 @@ struct ProcNetSockStat {
     udp_in_use: usize,
 }
 
-#[allow(dead_code)]
 fn proc_net_sockstat(input: &str) -> IResult<&str, ProcNetSockStat> {
     map(
         preceded(
@@ -504,9 +1551,598 @@ fn proc_net_sockstat(input: &str) -> IResult<&str, ProcNetSockStat> {
     .parse(input)
 }
 
-fn stat_mount(mount: ProcMountsData) -> io::Result<FileSystem> {
+/// Reads `/proc/net/sockstat`, the only source for the number of orphaned
+/// TCP sockets, i.e. sockets closed by their process but not yet by the
+/// kernel
+fn proc_sockstat(root: &Path) -> io::Result<ProcNetSockStat> {
+    read_file(rooted(root, "/proc/net/sockstat")).and_then(|data| {
+        proc_net_sockstat(&data).map(|(_, res)| res).map_err(|err| {
+            io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+        })
+    })
+}
+
+fn proc_net_dev_line(input: &str) -> IResult<&str, (&str, NetworkStats)> {
+    map(
+        complete((
+            ws(take_until(":")),
+            preceded(tag(":"), (num, num, num, num, num, num, num, num)),
+            (num, num, num, num, num, num, num, num),
+        )),
+        |(
+            name,
+            (
+                rx_bytes,
+                rx_packets,
+                rx_errors,
+                rx_dropped,
+                rx_fifo,
+                rx_frame,
+                rx_compressed,
+                multicast,
+            ),
+            (
+                tx_bytes,
+                tx_packets,
+                tx_errors,
+                tx_dropped,
+                tx_fifo,
+                collisions,
+                tx_carrier,
+                tx_compressed,
+            ),
+        )| {
+            (
+                name,
+                NetworkStats {
+                    rx_bytes: ByteSize::b(rx_bytes),
+                    tx_bytes: ByteSize::b(tx_bytes),
+                    rx_packets,
+                    tx_packets,
+                    rx_errors,
+                    tx_errors,
+                    rx_dropped,
+                    rx_fifo,
+                    rx_frame,
+                    rx_compressed,
+                    multicast,
+                    tx_dropped,
+                    tx_fifo,
+                    collisions,
+                    tx_carrier,
+                    tx_compressed,
+                },
+            )
+        },
+    )
+    .parse(input)
+}
+
+fn proc_net_dev(input: &str) -> IResult<&str, BTreeMap<String, NetworkStats>> {
+    fold_many0(
+        map_res(
+            verify(ws(not_line_ending), |item: &str| !item.is_empty()),
+            |input| {
+                opt(proc_net_dev_line)
+                    .parse(input)
+                    .map(|(_, res)| res)
+                    .map_err(|_| ())
+            },
+        ),
+        BTreeMap::new,
+        |mut map: BTreeMap<String, NetworkStats>, opt| {
+            if let Some((name, stats)) = opt {
+                map.insert(name.to_string(), stats);
+            }
+            map
+        },
+    )
+    .parse(input)
+}
+
+fn proc_net_dev_stats(
+    root: &Path,
+) -> io::Result<BTreeMap<String, NetworkStats>> {
+    read_file(rooted(root, "/proc/net/dev")).and_then(|data| {
+        proc_net_dev(&data).map(|(_, res)| res).map_err(|err| {
+            io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+        })
+    })
+}
+
+fn sys_network_stats(root: &Path, interface: &str) -> io::Result<NetworkStats> {
+    let path_root = rooted(root, "/sys/class/net")
+        .join(interface)
+        .join("statistics");
+    let counter = |file: &str| value_from_file::<u64>(&path_root.join(file));
+    let sum = |files: &[&str]| {
+        files.iter().try_fold(0u64, |sum, file| {
+            Ok::<_, io::Error>(sum.wrapping_add(counter(file)?))
+        })
+    };
+
+    // Folded the way dev_seq_printf_stats() does for /proc/net/dev
+    Ok(NetworkStats {
+        rx_bytes: ByteSize::b(counter("rx_bytes")?),
+        tx_bytes: ByteSize::b(counter("tx_bytes")?),
+        rx_packets: counter("rx_packets")?,
+        tx_packets: counter("tx_packets")?,
+        rx_errors: counter("rx_errors")?,
+        tx_errors: counter("tx_errors")?,
+        rx_dropped: sum(&["rx_dropped", "rx_missed_errors"])?,
+        rx_fifo: counter("rx_fifo_errors")?,
+        rx_frame: sum(&[
+            "rx_length_errors", "rx_over_errors", "rx_crc_errors",
+            "rx_frame_errors",
+        ])?,
+        rx_compressed: counter("rx_compressed")?,
+        multicast: counter("multicast")?,
+        tx_dropped: counter("tx_dropped")?,
+        tx_fifo: counter("tx_fifo_errors")?,
+        collisions: counter("collisions")?,
+        tx_carrier: sum(&[
+            "tx_carrier_errors",
+            "tx_aborted_errors",
+            "tx_window_errors",
+            "tx_heartbeat_errors",
+        ])?,
+        tx_compressed: counter("tx_compressed")?,
+    })
+}
+
+/// Names of the interfaces in `/sys/class/net`, skipping entries that are
+/// not interfaces, like the `bonding_masters` file of the bonding driver
+fn sys_class_net(root: &Path) -> io::Result<Vec<String>> {
+    let mut names = Vec::new();
+    for entry in std::fs::read_dir(rooted(root, "/sys/class/net"))? {
+        let entry = entry?;
+        if entry.path().join("ifindex").is_file() {
+            names.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    Ok(names)
+}
+
+fn all_network_stats(
+    root: &Path,
+) -> io::Result<BTreeMap<String, NetworkStats>> {
+    proc_net_dev_stats(root).or_else(|_| {
+        let mut stats = BTreeMap::new();
+        for name in sys_class_net(root)? {
+            let interface = sys_network_stats(root, &name)?;
+            stats.insert(name, interface);
+        }
+        Ok(stats)
+    })
+}
+
+#[test]
+fn test_sys_all_network_stats() {
+    let counters = [
+        "rx_bytes",
+        "tx_bytes",
+        "rx_packets",
+        "tx_packets",
+        "rx_errors",
+        "tx_errors",
+        "rx_dropped",
+        "rx_missed_errors",
+        "rx_fifo_errors",
+        "rx_length_errors",
+        "rx_over_errors",
+        "rx_crc_errors",
+        "rx_frame_errors",
+        "rx_compressed",
+        "multicast",
+        "tx_dropped",
+        "tx_fifo_errors",
+        "collisions",
+        "tx_carrier_errors",
+        "tx_aborted_errors",
+        "tx_window_errors",
+        "tx_heartbeat_errors",
+        "tx_compressed",
+    ];
+    let fixture = Fixture::new(
+        "net-stats",
+        counters
+            .iter()
+            .map(|counter| {
+                (format!("sys/class/net/bond0/statistics/{counter}"), "7\n")
+            })
+            .chain([
+                ("sys/class/net/bond0/ifindex".to_string(), "3\n"),
+                ("sys/class/net/bonding_masters".to_string(), "bond0\n"),
+            ]),
+    );
+
+    let stats = all_network_stats(fixture.root()).unwrap();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats["bond0"].rx_bytes, ByteSize::b(7));
+    assert_eq!(stats["bond0"].tx_compressed, 7);
+    // Same sums as the drop, frame and carrier columns of /proc/net/dev
+    assert_eq!(stats["bond0"].rx_dropped, 14);
+    assert_eq!(stats["bond0"].rx_frame, 28);
+    assert_eq!(stats["bond0"].tx_carrier, 28);
+}
+
+/// ARPHRD_ETHER from `if_arp.h`
+const ARPHRD_ETHER: u32 = 1;
+/// ARPHRD_LOOPBACK from `if_arp.h`
+const ARPHRD_LOOPBACK: u32 = 772;
+
+/// Whether `dir` has a `lower_<name>` link, which devices stacked on
+/// another one, like macvlan and ipvlan, get for their parent
+fn has_lower_device(dir: &Path) -> bool {
+    std::fs::read_dir(dir).is_ok_and(|entries| {
+        entries.flatten().any(|entry| {
+            entry.file_name().to_string_lossy().starts_with("lower_")
+        })
+    })
+}
+
+fn interface_kind(dir: &Path, link_type: u32, ifindex: u32) -> InterfaceKind {
+    let devtype = read_file(dir.join("uevent")).ok().and_then(|uevent| {
+        uevent
+            .lines()
+            .find_map(|line| line.strip_prefix("DEVTYPE="))
+            .map(str::to_string)
+    });
+
+    if link_type == ARPHRD_LOOPBACK {
+        InterfaceKind::Loopback
+    } else if dir.join("bridge").exists() {
+        InterfaceKind::Bridge
+    } else if dir.join("bonding").exists() {
+        InterfaceKind::Bond
+    } else if dir.join("tun_flags").exists() {
+        InterfaceKind::Tun
+    } else if devtype.as_deref() == Some("vlan") {
+        InterfaceKind::Vlan
+    } else if dir.join("device").exists() {
+        InterfaceKind::Physical
+    } else if link_type == ARPHRD_ETHER
+        && devtype.is_none()
+        && value_from_file::<u32>(&dir.join("iflink"))
+            .is_ok_and(|iflink| iflink != 0 && iflink != ifindex)
+        && !has_lower_device(dir)
+    {
+        // A veth points at its peer without being stacked on it. Tunnels
+        // have an iflink of 0 or aren't Ethernet, and macvlan or ipvlan
+        // have a `lower_` link to the parent they point at
+        InterfaceKind::Veth
+    } else {
+        InterfaceKind::Other
+    }
+}
+
+fn sys_network_link(root: &Path, name: &str) -> io::Result<NetworkLink> {
+    let dir = rooted(root, "/sys/class/net").join(name);
+    // speed, duplex and carrier fail with EINVAL while the interface is down
+    let attr = |file: &str| {
+        read_file(dir.join(file))
+            .ok()
+            .map(|value| value.trim_end_matches('\n').to_string())
+    };
+
+    let ifindex = value_from_file(&dir.join("ifindex"))?;
+    let link_type = value_from_file(&dir.join("type"))?;
+    let flags = attr("flags")
+        .and_then(|flags| {
+            u32::from_str_radix(flags.trim_start_matches("0x"), 16).ok()
+        })
+        .unwrap_or(0);
+
+    Ok(NetworkLink {
+        name: name.to_string(),
+        ifindex,
+        kind: interface_kind(&dir, link_type, ifindex),
+        mac: attr("address").filter(|mac| !mac.is_empty()),
+        mtu: value_from_file(&dir.join("mtu"))?,
+        speed: attr("speed").and_then(|speed| speed.parse().ok()),
+        duplex: attr("duplex")
+            .as_deref()
+            .map(Duplex::from)
+            .unwrap_or(Duplex::Unknown),
+        operstate: attr("operstate")
+            .as_deref()
+            .map(OperState::from)
+            .unwrap_or(OperState::Unknown),
+        carrier: attr("carrier").map(|carrier| carrier == "1"),
+        flags: InterfaceFlags(flags),
+    })
+}
+
+#[test]
+fn test_sys_network_link() {
+    let fixture = Fixture::empty("net");
+    let root = fixture.root();
+    let interface = |name: &str, attrs: &[(&str, &str)], dirs: &[&str]| {
+        for (file, value) in attrs {
+            fixture.write(
+                &format!("sys/class/net/{name}/{file}"),
+                format!("{value}\n"),
+            );
+        }
+        for sub in dirs {
+            fixture.write(&format!("sys/class/net/{name}/{sub}/"), "");
+        }
+    };
+    interface(
+        "eth0",
+        &[
+            ("ifindex", "2"),
+            ("iflink", "2"),
+            ("type", "1"),
+            ("address", "02:fc:00:00:00:01"),
+            ("mtu", "1500"),
+            ("speed", "10000"),
+            ("duplex", "full"),
+            ("operstate", "up"),
+            ("carrier", "1"),
+            ("flags", "0x1003"),
+        ],
+        &["device"],
+    );
+    interface(
+        "lo",
+        &[
+            ("ifindex", "1"),
+            ("type", "772"),
+            ("mtu", "65536"),
+            ("operstate", "unknown"),
+            ("flags", "0x9"),
+        ],
+        &[],
+    );
+    interface(
+        "br0",
+        &[("ifindex", "3"), ("type", "1"), ("mtu", "1500")],
+        &["bridge"],
+    );
+    interface(
+        "eth0.100",
+        &[
+            ("ifindex", "4"),
+            ("iflink", "2"),
+            ("type", "1"),
+            ("mtu", "1500"),
+            ("uevent", "DEVTYPE=vlan\nINTERFACE=eth0.100"),
+        ],
+        &[],
+    );
+    interface(
+        "veth0",
+        &[
+            ("ifindex", "5"),
+            ("iflink", "6"),
+            ("type", "1"),
+            ("mtu", "1500"),
+        ],
+        &[],
+    );
+    interface(
+        "gre0",
+        &[
+            ("ifindex", "7"),
+            ("iflink", "0"),
+            ("type", "778"),
+            ("mtu", "1476"),
+        ],
+        &[],
+    );
+    interface(
+        "gretap0",
+        &[
+            ("ifindex", "8"),
+            ("iflink", "0"),
+            ("type", "1"),
+            ("mtu", "1462"),
+        ],
+        &[],
+    );
+    interface(
+        "macvlan0",
+        &[
+            ("ifindex", "9"),
+            ("iflink", "2"),
+            ("type", "1"),
+            ("mtu", "1500"),
+        ],
+        &["lower_eth0"],
+    );
+
+    let eth0 = sys_network_link(root, "eth0").unwrap();
+    assert_eq!(eth0.kind, InterfaceKind::Physical);
+    assert_eq!(eth0.mac.as_deref(), Some("02:fc:00:00:00:01"));
+    assert_eq!(eth0.speed, Some(10000));
+    assert_eq!(eth0.duplex, Duplex::Full);
+    assert_eq!(eth0.operstate, OperState::Up);
+    assert_eq!(eth0.carrier, Some(true));
+    assert!(eth0.flags.is_up() && eth0.flags.is_multicast());
+
+    let lo = sys_network_link(root, "lo").unwrap();
+    assert_eq!(lo.kind, InterfaceKind::Loopback);
+    assert_eq!(lo.speed, None);
+    assert_eq!(lo.carrier, None);
+    assert!(lo.flags.is_loopback());
+
+    let kind = |name| sys_network_link(root, name).unwrap().kind;
+    assert_eq!(kind("br0"), InterfaceKind::Bridge);
+    assert_eq!(kind("eth0.100"), InterfaceKind::Vlan);
+    assert_eq!(kind("veth0"), InterfaceKind::Veth);
+    assert_eq!(kind("gre0"), InterfaceKind::Other);
+    assert_eq!(kind("gretap0"), InterfaceKind::Other);
+    assert_eq!(kind("macvlan0"), InterfaceKind::Other);
+
+    fixture.write("sys/class/net/bonding_masters", "\n");
+    let measurement = MeasurementImpl::with_root(root);
+    let links = measurement.network_links().unwrap();
+    assert_eq!(links.len(), 8);
+    assert!(!links.contains_key("bonding_masters"));
+}
+
+#[test]
+fn test_proc_net_dev() {
+    let input = "Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 31139861    3727    0    0    0     0          0         0 31139861    3727    0    0    0     0       0          0
+  eth0: 918273645 1234567    2   17    1     3          0       402 123456789  654321    0    5    0     7       4          0
+veth1a2b3c4:  16992      43    0    0    0     0          0         0     4942      37    0    0    0     0       0          0
+";
+    let result = proc_net_dev(input).unwrap().1;
+    assert_eq!(result.len(), 3);
+    let eth0 = result.get("eth0").unwrap();
+    assert_eq!(eth0.rx_bytes, ByteSize::b(918273645));
+    assert_eq!(eth0.rx_packets, 1234567);
+    assert_eq!(eth0.rx_errors, 2);
+    assert_eq!(eth0.rx_dropped, 17);
+    assert_eq!(eth0.rx_fifo, 1);
+    assert_eq!(eth0.rx_frame, 3);
+    assert_eq!(eth0.multicast, 402);
+    assert_eq!(eth0.tx_bytes, ByteSize::b(123456789));
+    assert_eq!(eth0.tx_dropped, 5);
+    assert_eq!(eth0.collisions, 7);
+    assert_eq!(eth0.tx_carrier, 4);
+    assert_eq!(result.get("veth1a2b3c4").unwrap().tx_packets, 37);
+}
+
+/// A `Section: a b c` line of `/proc/net/snmp` or `/proc/net/netstat`
+fn proc_net_snmp_line(input: &str) -> IResult<&str, (&str, Vec<&str>)> {
+    (
+        take_until(":"),
+        preceded(
+            tag(":"),
+            many0(preceded(space0, take_till1(|c| is_space(c as u8)))),
+        ),
+    )
+        .parse(input)
+}
+
+/// Parses `/proc/net/snmp` and `/proc/net/netstat`, where each section is a
+/// line of names followed by a line of values
+fn proc_net_snmp<'a>(
+    input: &'a str,
+    counters: &mut ProtocolCounters,
+) -> Result<(), nom::Err<nom::error::Error<&'a str>>> {
+    let mut names: Option<(&str, Vec<&str>)> = None;
+    for line in input.lines().filter(|line| !line.is_empty()) {
+        let (section, fields) = proc_net_snmp_line(line)?.1;
+        match names.take() {
+            Some((names_section, names)) if names_section == section => {
+                for (name, value) in names.into_iter().zip(fields) {
+                    let value = character::complete::i64(value)?.1;
+                    counters.set(section, name, value);
+                }
+            }
+            _ => names = Some((section, fields)),
+        }
+    }
+    Ok(())
+}
+
+/// Parses `/proc/net/snmp6`, one `<Section><Name> <value>` per line
+fn proc_net_snmp6<'a>(
+    input: &'a str,
+    counters: &mut ProtocolCounters,
+) -> Result<(), nom::Err<nom::error::Error<&'a str>>> {
+    const SECTIONS: [&str; 4] = ["Ip6", "Icmp6", "UdpLite6", "Udp6"];
+
+    for line in input.lines().filter(|line| !line.is_empty()) {
+        let (key, value) =
+            (ws(word_s), ws(character::complete::i64)).parse(line)?.1;
+        let (section, name) = SECTIONS
+            .iter()
+            .find_map(|section| {
+                key.strip_prefix(section).map(|name| (*section, name))
+            })
+            .unwrap_or(("Snmp6", key));
+        counters.set(section, name, value);
+    }
+    Ok(())
+}
+
+fn protocol_counters(root: &Path) -> io::Result<ProtocolCounters> {
+    let invalid_data = |err: nom::Err<nom::error::Error<&str>>| -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+    };
+    // netstat and snmp6 are missing on old kernels and without IPv6
+    let optional = |path: &str| match read_file(rooted(root, path)) {
+        Ok(data) => Ok(Some(data)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    };
+
+    let mut counters = ProtocolCounters::default();
+    let snmp = read_file(rooted(root, "/proc/net/snmp"))?;
+    proc_net_snmp(&snmp, &mut counters).map_err(invalid_data)?;
+    if let Some(netstat) = optional("/proc/net/netstat")? {
+        proc_net_snmp(&netstat, &mut counters).map_err(invalid_data)?;
+    }
+    if let Some(snmp6) = optional("/proc/net/snmp6")? {
+        proc_net_snmp6(&snmp6, &mut counters).map_err(invalid_data)?;
+    }
+    Ok(counters)
+}
+
+#[test]
+fn test_proc_net_snmp() {
+    let snmp = "Ip: Forwarding DefaultTTL InReceives InHdrErrors InAddrErrors ForwDatagrams InUnknownProtos InDiscards InDelivers OutRequests OutDiscards OutNoRoutes ReasmTimeout ReasmReqds ReasmOKs ReasmFails FragOKs FragFails FragCreates OutTransmits
+Ip: 2 64 5480 0 0 0 0 2 5478 5506 1 0 0 0 0 4 0 0 0 5506
+Icmp: InMsgs InErrors InCsumErrors InDestUnreachs InTimeExcds OutMsgs OutErrors OutRateLimitGlobal OutRateLimitHost OutDestUnreachs
+Icmp: 3 1 0 3 0 6 0 0 0 6
+IcmpMsg: InType3 OutType3
+IcmpMsg: 3 3
+Tcp: RtoAlgorithm RtoMin RtoMax MaxConn ActiveOpens PassiveOpens AttemptFails EstabResets CurrEstab InSegs OutSegs RetransSegs InErrs OutRsts InCsumErrors
+Tcp: 1 200 120000 -1 9 7 0 6 2 5472 5494 12 0 2 0
+Udp: InDatagrams NoPorts InErrors OutDatagrams RcvbufErrors SndbufErrors InCsumErrors IgnoredMulti MemErrors
+Udp: 8 0 0 8 5 0 0 0 0
+";
+    let netstat =
+        "TcpExt: SyncookiesSent SyncookiesRecv ListenOverflows ListenDrops 
+TcpExt: 0 0 17 19
+IpExt: InNoRoutes InOctets
+IpExt: 0 48293125
+";
+    let snmp6 = "Ip6InReceives                   \t5
+Icmp6InMsgs                     \t2
+Udp6RcvbufErrors                \t4
+UdpLite6InDatagrams             \t0
+";
+    let mut counters = ProtocolCounters::default();
+    proc_net_snmp(snmp, &mut counters).unwrap();
+    proc_net_snmp(netstat, &mut counters).unwrap();
+    proc_net_snmp6(snmp6, &mut counters).unwrap();
+
+    assert_eq!(counters.ip.in_receives, 5480);
+    assert_eq!(counters.ip.in_discards, 2);
+    assert_eq!(counters.ip.out_requests, 5506);
+    assert_eq!(counters.ip.reasm_fails, 4);
+    assert_eq!(counters.ip.other.get("Forwarding"), 2);
+    assert_eq!(counters.icmp.in_msgs, 3);
+    assert_eq!(counters.icmp.in_errors, 1);
+    assert_eq!(counters.icmp.in_dest_unreachs, 3);
+    assert_eq!(counters.icmp.out_msgs, 6);
+    assert_eq!(counters.icmp.other.get("InTimeExcds"), 0);
+    assert_eq!(counters.tcp.other.get("MaxConn"), -1);
+    assert_eq!(counters.tcp.retrans_segs, 12);
+    assert_eq!(counters.tcp.in_errs, 0);
+    assert_eq!(counters.udp.rcvbuf_errors, 5);
+    assert_eq!(counters.udp.in_errors, 0);
+    assert_eq!(counters.tcp_ext.listen_overflows, 17);
+    assert_eq!(counters.tcp_ext.listen_drops, 19);
+    assert_eq!(counters.tcp_ext.other.counters.len(), 2);
+    assert_eq!(counters.ip_ext.get("InOctets"), 48293125);
+    assert_eq!(counters.other["IcmpMsg"].get("InType3"), 3);
+    assert_eq!(counters.ip6.get("InReceives"), 5);
+    assert_eq!(counters.icmp6.get("InMsgs"), 2);
+    assert_eq!(counters.udp6.rcvbuf_errors, 4);
+    assert_eq!(counters.udp_lite6.counters.len(), 1);
+}
+
+fn stat_mount(root: &Path, mount: ProcMountsData) -> io::Result<FileSystem> {
     let mut info = unsafe { mem::zeroed::<libc::statvfs>() };
-    let target = format!("{}\0", mount.target);
+    let target = format!("{}\0", rooted(root, &mount.target).display());
     let result = unsafe { statvfs(target.as_ptr() as *const i8, &mut info) };
     match result {
         0 => Ok(FileSystem {
@@ -543,6 +2179,7 @@ FRAG: inuse 0 memory 0
     assert_eq!(result.udp_in_use, 1);
 }
 
+#[allow(dead_code)]
 struct ProcNetSockStat6 {
     tcp_in_use: usize,
     udp_in_use: usize,
@@ -572,61 +2209,8 @@ RAW6: inuse 1
 FRAG6: inuse 0 memory 0
 ";
     let result = proc_net_sockstat6(input).unwrap().1;
-    assert_eq!(result.tcp_in_use, 3);
-    assert_eq!(result.udp_in_use, 1);
-}
-
-struct TcpSockStat {
-    in_use: usize,
-    orphaned: usize,
-    time_wait: usize,
-}
-
-impl Default for TcpSockStat {
-    fn default() -> Self {
-        TcpSockStat {
-            in_use: 0,
-            orphaned: 0,
-            time_wait: 0,
-        }
-    }
-}
-
-impl From<TcpSockStat> for ProcNetSockStat {
-    fn from(stat: TcpSockStat) -> Self {
-        ProcNetSockStat {
-            tcp_in_use: stat.in_use,
-            tcp_orphaned: stat.orphaned,
-            tcp_time_wait: stat.time_wait,
-            ..Default::default()
-        }
-    }
-}
-
-fn parse_tcp_state(line: &str) -> Option<u8> {
-    let parts = line.split_whitespace().collect::<Vec<&str>>();
-    if parts.len() < 4 {
-        return None;
-    }
-    u8::from_str_radix(parts[3], 16).ok()
-}
-
-fn tcp_sock_from_raw(content: &str) -> std::io::Result<TcpSockStat> {
-    let mut stat = TcpSockStat::default();
-    for line in content.lines().skip(1) {
-        if let Some(state) = parse_tcp_state(line) {
-            // stat.in_use += 1;
-            match state {
-                0x06 => stat.time_wait += 1, // TIME_WAIT
-                0x0B => stat.orphaned += 1,  // CLOSE_WAIT (as an example)
-                _ => {
-                    stat.in_use += 1;
-                }
-            }
-        }
-    }
-
-    Ok(stat)
+    assert_eq!(result.tcp_in_use, 3);
+    assert_eq!(result.udp_in_use, 1);
 }
 
 #[test]
@@ -654,21 +2238,10 @@ fn test_tcp_sock_raw() {
   19: 0100007F:6DB0 0100007F:E152 06 00000000:00000000 03:00000C61 00000000     0        0 0 3 ffff967b2563ad90                                      
   20: 4F000A0A:B412 2916ED04:01BB 01 00000000:00000000 02:0000090E 00000000  1000        0 96625872 2 ffff967a6e213480 36 4 1 10 24                  
   21: 4F000A0A:D43C 69825514:01BB 01 00000000:00000000 02:0000093F 00000000  1000        0 96682339 2 ffff9679cd939a40 55 4 12 14 -1"#;
-    let res = tcp_sock_from_raw(content).unwrap();
-    assert_eq!(res.in_use, 17);
-    assert_eq!(res.orphaned, 0);
-    assert_eq!(res.time_wait, 5);
-}
-
-fn udp_sock_from_raw(content: &str) -> std::io::Result<usize> {
-    let mut in_use = 0;
-    for line in content.lines().skip(1) {
-        let parts = line.split_whitespace().collect::<Vec<&str>>();
-        if parts.len() >= 4 {
-            in_use += 1;
-        }
-    }
-    Ok(in_use)
+    let sockets = proc_net_sockets(SocketProtocol::Tcp, content).unwrap().1;
+    let res = socket_stats_from(&sockets);
+    assert_eq!(res.tcp_sockets_in_use, 17);
+    assert_eq!(res.tcp_sockets_time_wait, 5);
 }
 
 #[test]
@@ -680,26 +2253,553 @@ fn test_udp_sock_raw() {
 2104: 00000000:E24A 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 92612858 2 ffff967a6c186300 0      
 3326: 0100007F:2710 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 381284 2 ffff967b69441b00 0        
 4092: 00000000:CA0E 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 95211316 2 ffff9679858b1680 0"#;
-    let res = udp_sock_from_raw(content).unwrap();
-    assert_eq!(res, 6);
+    let sockets = proc_net_sockets(SocketProtocol::Udp, content).unwrap().1;
+    let res = socket_stats_from(&sockets);
+    assert_eq!(res.udp_sockets_in_use, 6);
 }
 
-fn proc_sockstat_from_raw() -> io::Result<ProcNetSockStat> {
-    let tcp_content = read_file("/proc/net/tcp")?;
-    let tcp_stat = tcp_sock_from_raw(&tcp_content)?;
-    let mut retval: ProcNetSockStat = tcp_stat.into();
+fn hex_u64(input: &str) -> IResult<&str, u64> {
+    map_res(hex_digit1, |hex| u64::from_str_radix(hex, 16)).parse(input)
+}
 
-    let udp_content = read_file("/proc/net/udp")?;
-    let udp_in_use = udp_sock_from_raw(&udp_content)?;
-    retval.udp_in_use = udp_in_use;
+/// Decodes an address as printed by the kernel: the network order bytes,
+/// printed as native endian 32-bit words, then the port in host order
+fn proc_net_addr(input: &str) -> IResult<&str, SocketAddr> {
+    map_res(
+        (hex_digit1, preceded(tag(":"), hex_u64)),
+        |(addr, port): (&str, u64)| -> Result<SocketAddr, ()> {
+            let bytes = (0..addr.len())
+                .step_by(8)
+                .map(|i| {
+                    addr.get(i..i + 8)
+                        .and_then(|word| u32::from_str_radix(word, 16).ok())
+                        .map(u32::to_ne_bytes)
+                        .ok_or(())
+                })
+                .collect::<Result<Vec<[u8; 4]>, ()>>()?
+                .concat();
+            let ip = match bytes.len() {
+                4 => IpAddr::from(<[u8; 4]>::try_from(bytes).map_err(|_| ())?),
+                16 => {
+                    IpAddr::from(<[u8; 16]>::try_from(bytes).map_err(|_| ())?)
+                }
+                _ => return Err(()),
+            };
+            Ok(SocketAddr::new(ip, u16::try_from(port).map_err(|_| ())?))
+        },
+    )
+    .parse(input)
+}
 
-    Ok(retval)
+fn proc_net_socket_line(
+    protocol: SocketProtocol,
+    input: &str,
+) -> IResult<&str, Socket> {
+    map(
+        (
+            preceded((ws(digit1), tag(":")), ws(proc_net_addr)),
+            ws(proc_net_addr),
+            ws(hex_u64),
+            ws((hex_u64, preceded(tag(":"), hex_u64))),
+            ws((hex_u64, preceded(tag(":"), hex_u64))),
+            ws(hex_u64),
+            (num, num::<u64>, num),
+        ),
+        |(
+            local,
+            remote,
+            state,
+            (tx_queue, rx_queue),
+            (timer, expires),
+            retransmits,
+            (uid, _timeout, inode),
+        )| Socket {
+            protocol,
+            local,
+            remote,
+            state: TcpState::from(state as u8),
+            tx_queue,
+            rx_queue,
+            timer: SocketTimer::from(timer as u8),
+            // Printed in clock ticks, a negative delta means it's overdue
+            timer_expires: Duration::from_secs_f64(
+                i64::try_from(expires).unwrap_or(0) as f64 / clock_ticks(),
+            ),
+            retransmits,
+            uid,
+            inode,
+            tcp_info: None,
+        },
+    )
+    .parse(input)
+}
+
+fn proc_net_sockets(
+    protocol: SocketProtocol,
+    input: &str,
+) -> IResult<&str, Vec<Socket>> {
+    fold_many0(
+        map_res(
+            verify(ws(not_line_ending), |item: &str| !item.is_empty()),
+            |input| {
+                opt(|input| proc_net_socket_line(protocol, input))
+                    .parse(input)
+                    .map(|(_, res)| res)
+                    .map_err(|_| ())
+            },
+        ),
+        Vec::new,
+        |mut sockets: Vec<Socket>, opt| {
+            sockets.extend(opt);
+            sockets
+        },
+    )
+    .parse(input)
+}
+
+fn clock_ticks() -> f64 {
+    unsafe { libc::sysconf(libc::_SC_CLK_TCK) as f64 }
+}
+
+/// Reads the sockets of the network namespace `net_dir` belongs to, either
+/// `/proc/net` or `/proc/<pid>/net`
+fn sockets(net_dir: &Path) -> io::Result<Vec<Socket>> {
+    let mut sockets = Vec::new();
+    for (protocol, file) in [
+        (SocketProtocol::Tcp, "tcp"),
+        (SocketProtocol::Tcp6, "tcp6"),
+        (SocketProtocol::Udp, "udp"),
+        (SocketProtocol::Udp6, "udp6"),
+    ] {
+        let data = match read_file(net_dir.join(file)) {
+            Ok(data) => data,
+            // IPv6 disabled
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        let parsed = proc_net_sockets(protocol, &data).map_err(|err| {
+            io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+        })?;
+        sockets.extend(parsed.1);
+    }
+    Ok(sockets)
+}
+
+/// `__SO_ACCEPTCON`, set in the flags of listening sockets
+const SO_ACCEPTCON: u64 = 1 << 16;
+
+fn proc_net_unix_line(input: &str) -> IResult<&str, UnixSocket> {
+    map(
+        (
+            terminated(hex_digit1, tag(":")),
+            (ws(hex_u64), ws(hex_u64), ws(hex_u64)),
+            (ws(hex_u64), ws(hex_u64), num::<u64>),
+            not_line_ending,
+        ),
+        |(
+            _,
+            (ref_count, _protocol, flags),
+            (socket_type, state, inode),
+            path,
+        )| {
+            let state = if flags & SO_ACCEPTCON != 0 {
+                UnixSocketState::Listening
+            } else {
+                UnixSocketState::from(state as u8)
+            };
+            UnixSocket {
+                socket_type: (socket_type as u16).into(),
+                state,
+                ref_count: ref_count as u32,
+                inode,
+                path: Some(path)
+                    .filter(|path| !path.is_empty())
+                    .map(str::to_string),
+            }
+        },
+    )
+    .parse(input)
+}
+
+/// Parses `/proc/net/unix`, skipping the header and rows that don't parse.
+///
+/// The kernel prints paths as raw bytes, so any process can bind a name
+/// containing a newline, which splits its row in two
+fn proc_net_unix(input: &str) -> Vec<UnixSocket> {
+    let mut skipped = 0;
+    let sockets = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("Num"))
+        .filter_map(|line| match proc_net_unix_line(line) {
+            Ok((_, socket)) => Some(socket),
+            Err(_) => {
+                skipped += 1;
+                None
+            }
+        })
+        .collect();
+    if skipped > 0 {
+        log::debug!("Skipped {skipped} malformed rows of /proc/net/unix");
+    }
+    sockets
+}
+
+fn unix_sockets(root: &Path) -> io::Result<Vec<UnixSocket>> {
+    // Paths aren't necessarily UTF-8
+    let data = std::fs::read(rooted(root, "/proc/net/unix"))?;
+    Ok(proc_net_unix(&String::from_utf8_lossy(&data)))
+}
+
+#[test]
+fn test_proc_net_unix() {
+    use crate::network::{UnixSocketStats, UnixSocketType};
+
+    let unix = "Num       RefCount Protocol Flags    Type St Inode Path
+0000000000a0df99: 00000003 00000000 00000000 0001 03   659
+00000000b9aa169d: 00000002 00000000 00010000 0001 01  3306 /run/app.sock
+0000000041f8fd85: 00000003 00000000 00000000 0001 03 21895 /run/app.sock
+00000000bebcfab1: 00000002 00000000 00000000 0002 01 13214 @/tmp/dbus-Xa1b2
+0000000000000000: 00000002 00000000 00000000 0005 01 17760 /run/my app/ctl
+";
+    let sockets = proc_net_unix(unix);
+    assert_eq!(sockets.len(), 5);
+    assert_eq!(sockets[0].socket_type, UnixSocketType::Stream);
+    assert_eq!(sockets[0].state, UnixSocketState::Connected);
+    assert_eq!(sockets[0].ref_count, 3);
+    assert_eq!(sockets[0].inode, 659);
+    assert_eq!(sockets[0].path, None);
+    assert_eq!(sockets[1].state, UnixSocketState::Listening);
+    assert_eq!(sockets[1].path.as_deref(), Some("/run/app.sock"));
+    assert_eq!(sockets[3].socket_type, UnixSocketType::Dgram);
+    assert_eq!(sockets[3].state, UnixSocketState::Unconnected);
+    assert_eq!(sockets[3].path.as_deref(), Some("@/tmp/dbus-Xa1b2"));
+    assert_eq!(sockets[4].socket_type, UnixSocketType::SeqPacket);
+    assert_eq!(sockets[4].path.as_deref(), Some("/run/my app/ctl"));
+
+    let stats: UnixSocketStats = sockets.iter().collect();
+    assert_eq!(stats.stream[&UnixSocketState::Connected], 2);
+    assert_eq!(stats.by_path["/run/app.sock"], 2);
+
+    let malformed = "Num       RefCount Protocol Flags    Type St Inode Path
+0000000000a0df99: 00000003 00000000 00000000 0001 03   659
+00000000b9aa169d: 00000002 00000000
+00000000bebcfab1: 00000002 00000000 00000000 0002 01 13214 @evil
+name
+";
+    let sockets = proc_net_unix(malformed);
+    assert_eq!(sockets.len(), 2);
+    assert_eq!(sockets[1].path.as_deref(), Some("@evil"));
+
+    let non_utf8 = b"Num       RefCount Protocol Flags    Type St Inode Path
+00000000bebcfab1: 00000002 00000000 00000000 0002 01 13214 @\xff\xfe
+0000000000a0df99: 00000003 00000000 00000000 0001 03   659
+";
+    let fixture = Fixture::new("unix", [("proc/net/unix", non_utf8)]);
+    let sockets = unix_sockets(fixture.root()).unwrap();
+    assert_eq!(sockets.len(), 2);
+    assert_eq!(sockets[0].path.as_deref(), Some("@\u{fffd}\u{fffd}"));
+}
+
+/// Counts the entries of a `/proc/net` table, zero when the protocol is not
+/// available
+fn proc_net_entries(root: &Path, path: &str) -> io::Result<usize> {
+    match read_file(rooted(root, path)) {
+        Ok(data) => Ok(data.lines().skip(1).count()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(0),
+        Err(err) => Err(err),
+    }
+}
+
+/// Adds the raw, SCTP and unix domain sockets to TCP and UDP `stats`
+fn with_other_sockets(
+    root: &Path,
+    mut stats: SocketStats,
+) -> io::Result<SocketStats> {
+    stats.raw_sockets_in_use = proc_net_entries(root, "/proc/net/raw")?;
+    stats.raw6_sockets_in_use = proc_net_entries(root, "/proc/net/raw6")?;
+    stats.sctp_associations = proc_net_entries(root, "/proc/net/sctp/assocs")?;
+    stats.sctp_endpoints = proc_net_entries(root, "/proc/net/sctp/eps")?;
+    // Not worth failing the TCP and UDP stats over
+    stats.unix = match unix_sockets(root) {
+        Ok(sockets) => sockets.iter().collect(),
+        // Unix domain sockets are built as a module and not loaded
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Default::default(),
+        Err(err) => {
+            log::warn!("Reading /proc/net/unix failed: {err}");
+            Default::default()
+        }
+    };
+    Ok(stats)
+}
+
+#[test]
+fn test_with_other_sockets_missing() {
+    let fixture = Fixture::empty("other-sockets");
+    let stats =
+        with_other_sockets(fixture.root(), SocketStats::default()).unwrap();
+    assert_eq!(stats.raw_sockets_in_use, 0);
+    assert_eq!(stats.unix.stream.len(), 0);
+
+    // Unreadable rather than missing
+    fixture.write("proc/net/unix/", "");
+    let stats =
+        with_other_sockets(fixture.root(), SocketStats::default()).unwrap();
+    assert_eq!(stats.unix.stream.len(), 0);
+}
+
+/// Maps the inodes of the sockets `pid` has open to their descriptors,
+/// several when a socket was dup'd
+fn socket_fds(root: &Path, pid: u32) -> io::Result<BTreeMap<u64, Vec<u32>>> {
+    let mut fds: BTreeMap<u64, Vec<u32>> = BTreeMap::new();
+    for entry in std::fs::read_dir(rooted(root, format!("/proc/{pid}/fd")))? {
+        let entry = entry?;
+        // The descriptor may be closed while we look
+        let Ok(target) = std::fs::read_link(entry.path()) else {
+            continue;
+        };
+        let inode = target
+            .to_str()
+            .and_then(|target| target.strip_prefix("socket:["))
+            .and_then(|target| target.strip_suffix(']'))
+            .and_then(|inode| inode.parse().ok());
+        let fd = entry.file_name().to_str().and_then(|fd| fd.parse().ok());
+        if let (Some(inode), Some(fd)) = (inode, fd) {
+            fds.entry(inode).or_default().push(fd);
+        }
+    }
+    // Directory order is arbitrary
+    fds.values_mut().for_each(|fds| fds.sort_unstable());
+    Ok(fds)
+}
+
+fn socket_owners(root: &Path) -> io::Result<BTreeMap<u64, Vec<SocketOwner>>> {
+    let mut owners: BTreeMap<u64, Vec<SocketOwner>> = BTreeMap::new();
+    for entry in std::fs::read_dir(rooted(root, "/proc"))? {
+        let Some(pid) = entry?
+            .file_name()
+            .to_str()
+            .and_then(|pid| pid.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        // Other users' processes or processes that exited meanwhile
+        let Ok(fds) = socket_fds(root, pid) else {
+            continue;
+        };
+        let name = read_file(rooted(root, format!("/proc/{pid}/comm")))
+            .map(|comm| comm.trim_end_matches('\n').to_string())
+            .unwrap_or_default();
+        for (inode, fds) in fds {
+            owners.entry(inode).or_default().extend(fds.into_iter().map(
+                |fd| SocketOwner {
+                    pid,
+                    name: name.clone(),
+                    fd,
+                },
+            ));
+        }
+    }
+    Ok(owners)
+}
+
+fn listening_ports(
+    root: &Path,
+    backend: SocketBackend,
+) -> io::Result<Vec<ListeningPort>> {
+    let mut owners = socket_owners(root)?;
+    Ok(host_sockets(root, backend, true)?
+        .into_iter()
+        .filter(|socket| match socket.protocol {
+            SocketProtocol::Tcp | SocketProtocol::Tcp6 => {
+                socket.state == TcpState::Listen
+            }
+            SocketProtocol::Udp | SocketProtocol::Udp6 => {
+                socket.state == TcpState::Close && socket.local.port() != 0
+            }
+        })
+        .map(|socket| ListeningPort {
+            owners: owners.remove(&socket.inode).unwrap_or_default(),
+            socket,
+        })
+        .collect())
+}
+
+#[test]
+fn test_listening_ports() {
+    let tcp = r#"  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:0016 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 26714 1 ffff967b6f7571c0 100 0 0 10 0
+   1: 4F000A0A:0016 01000A0A:EC8F 01 00000024:00000000 01:00000014 00000000     0        0 96540557 4 ffff967b6f8c1180 21 4 31 7 7
+"#;
+    let udp = r#"   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+ 1571: 3500007F:0035 00000000:0000 07 00000000:00000000 00:00000000 00000000   101        0 22068 2 ffff967b626e3180 0
+"#;
+    let fixture = Fixture::new(
+        "ports",
+        [
+            ("proc/net/tcp", tcp),
+            ("proc/net/udp", udp),
+            ("proc/42/net/tcp", tcp),
+            ("proc/42/net/udp", udp),
+            ("proc/42/comm", "sshd\n"),
+        ],
+    );
+    fixture.symlink("socket:[26714]", "proc/42/fd/3");
+    fixture.symlink("socket:[96540557]", "proc/42/fd/4");
+    // dup'd listener
+    fixture.symlink("socket:[26714]", "proc/42/fd/5");
+    fixture.symlink("/dev/null", "proc/42/fd/0");
+
+    let ports = listening_ports(fixture.root(), SocketBackend::Proc).unwrap();
+    assert_eq!(ports.len(), 2);
+    assert_eq!(ports[0].port(), 22);
+    assert_eq!(
+        ports[0].owners,
+        vec![
+            SocketOwner {
+                pid: 42,
+                name: "sshd".to_string(),
+                fd: 3
+            },
+            SocketOwner {
+                pid: 42,
+                name: "sshd".to_string(),
+                fd: 5
+            }
+        ]
+    );
+    assert_eq!(ports[1].socket.protocol, SocketProtocol::Udp);
+    assert_eq!(ports[1].port(), 53);
+    assert!(ports[1].owners.is_empty());
+
+    let measurement = MeasurementImpl::with_root(fixture.root());
+    let sockets = measurement.sockets_by_pid(42).unwrap();
+    assert_eq!(sockets.len(), 2);
+    assert_eq!(sockets[1].inode, 96540557);
+}
+
+#[test]
+fn test_socket_backend() {
+    let tcp = r#"  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:0016 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 26714 1 ffff967b6f7571c0 100 0 0 10 0
+"#;
+    let fixture = Fixture::new("backend", [("proc/net/tcp", tcp)]);
+
+    // A custom root reads its own tables rather than the caller's netlink
+    let measurement = MeasurementImpl::with_root(fixture.root());
+    assert_eq!(measurement.socket_backend, SocketBackend::Proc);
+    let sockets = measurement.sockets().unwrap();
+    assert_eq!(sockets.len(), 1);
+    assert_eq!(sockets[0].inode, 26714);
+
+    let measurement = MeasurementImpl::new();
+    assert_eq!(measurement.socket_backend, SocketBackend::Auto);
+}
+
+fn host_sockets(
+    root: &Path,
+    backend: SocketBackend,
+    tcp_info: bool,
+) -> io::Result<Vec<Socket>> {
+    match backend {
+        SocketBackend::Netlink => sock_diag::sockets(tcp_info),
+        SocketBackend::Proc => sockets(&rooted(root, "/proc/net")),
+        SocketBackend::Auto => sock_diag::sockets(tcp_info).or_else(|err| {
+            log::warn!(
+                "Reading sockets over netlink failed, using /proc/net: {err}"
+            );
+            sockets(&rooted(root, "/proc/net"))
+        }),
+    }
+}
+
+/// Counts sockets like `/proc/net/sockstat` and `sockstat6` do. Orphans
+/// can't be told apart in a socket listing and are left at zero, see
+/// `proc_sockstat`
+pub(super) fn socket_stats_from(sockets: &[Socket]) -> SocketStats {
+    let mut stats = SocketStats::default();
+    for socket in sockets {
+        match socket.protocol {
+            // `tw` in sockstat covers both, sockstat6 `inuse` excludes them
+            SocketProtocol::Tcp | SocketProtocol::Tcp6
+                if socket.state == TcpState::TimeWait =>
+            {
+                stats.tcp_sockets_time_wait += 1
+            }
+            SocketProtocol::Tcp => stats.tcp_sockets_in_use += 1,
+            SocketProtocol::Tcp6 => stats.tcp6_sockets_in_use += 1,
+            SocketProtocol::Udp => stats.udp_sockets_in_use += 1,
+            SocketProtocol::Udp6 => stats.udp6_sockets_in_use += 1,
+        }
+        if matches!(socket.protocol, SocketProtocol::Tcp | SocketProtocol::Tcp6)
+        {
+            *stats.tcp_states.entry(socket.state).or_insert(0) += 1;
+        }
+    }
+    stats
+}
+
+#[test]
+#[ignore]
+fn test_sock_diag_matches_proc() {
+    let proc = socket_stats_from(&sockets(Path::new("/proc/net")).unwrap());
+    let netlink = socket_stats_from(&sock_diag::sockets(false).unwrap());
+    assert_eq!(proc.tcp_states, netlink.tcp_states);
+    assert_eq!(proc.udp_sockets_in_use, netlink.udp_sockets_in_use);
+}
+
+#[test]
+fn test_proc_net_sockets() {
+    let tcp = r#"  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 3500007F:0035 00000000:0000 0A 00000000:00000000 00:00000000 00000000   101        0 22069 1 ffff967b68b8cec0 100 0 0 10 0
+   1: 4F000A0A:E9E8 8FDE3967:8236 06 00000000:00000000 03:00000215 00000000     0        0 0 3 ffff967b6c31f740
+   2: 4F000A0A:0016 01000A0A:EC8F 01 00000024:00000010 01:00000014 00000002     0        0 96540557 4 ffff967b6f8c1180 21 4 31 7 7
+"#;
+    let sockets = proc_net_sockets(SocketProtocol::Tcp, tcp).unwrap().1;
+    assert_eq!(sockets.len(), 3);
+    assert_eq!(sockets[0].local, "127.0.0.53:53".parse().unwrap());
+    assert_eq!(sockets[0].state, TcpState::Listen);
+    assert_eq!(sockets[0].uid, 101);
+    assert_eq!(sockets[0].inode, 22069);
+    assert_eq!(sockets[1].remote, "103.57.222.143:33334".parse().unwrap());
+    assert_eq!(sockets[1].state, TcpState::TimeWait);
+    assert_eq!(sockets[1].timer, SocketTimer::TimeWait);
+    assert_eq!(sockets[2].local, "10.10.0.79:22".parse().unwrap());
+    assert_eq!(sockets[2].state, TcpState::Established);
+    assert_eq!(sockets[2].tx_queue, 0x24);
+    assert_eq!(sockets[2].rx_queue, 0x10);
+    assert_eq!(sockets[2].timer, SocketTimer::Retransmit);
+    assert_eq!(sockets[2].retransmits, 2);
+
+    let tcp6 = r#"  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000000000000:0016 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 26716 1 ffff967b6f7571c0 100 0 0 10 0
+   1: 0000000000000000FFFF00000100007F:9C45 0000000000000000FFFF00000100007F:B87E 01 00000000:00000000 00:00000000 00000000  1000        0 96620250 1 ffff967b6f752300 21 4 0 10 16
+   2: 0000000000000000FFFF00000100007F:9C46 0000000000000000FFFF00000100007F:B87E 06 00000000:00000000 03:00000215 00000000     0        0 0 3 ffff967b6c31f740
+"#;
+    let sockets6 = proc_net_sockets(SocketProtocol::Tcp6, tcp6).unwrap().1;
+    assert_eq!(sockets6.len(), 3);
+    assert_eq!(sockets6[0].local, "[::]:22".parse().unwrap());
+    assert_eq!(
+        sockets6[1].local,
+        "[::ffff:127.0.0.1]:40005".parse().unwrap()
+    );
+
+    let stats = socket_stats_from(&[sockets, sockets6].concat());
+    assert_eq!(stats.tcp_sockets_in_use, 2);
+    // One IPv4 and one IPv6 socket in TIME_WAIT
+    assert_eq!(stats.tcp_sockets_time_wait, 2);
+    assert_eq!(stats.tcp6_sockets_in_use, 2);
+    let states = &stats.tcp_states;
+    assert_eq!(states.get(&TcpState::Listen), Some(&2));
+    assert_eq!(states.get(&TcpState::Established), Some(&2));
+    assert_eq!(states.get(&TcpState::TimeWait), Some(&2));
+    assert_eq!(states.get(&TcpState::CloseWait), None);
 }
 
 #[test]
 #[ignore]
 fn test_proc_raw() {
-    let res = proc_sockstat_from_raw().unwrap();
+    let res = socket_stats_from(&sockets(Path::new("/proc/net")).unwrap());
     let sockstats = read_file("/proc/net/sockstat")
         .and_then(|data| {
             proc_net_sockstat(&data).map(|(_, res)| res).map_err(|e| {
@@ -708,29 +2808,15 @@ fn test_proc_raw() {
         })
         .unwrap();
 
-    assert_eq!(res.tcp_in_use, sockstats.tcp_in_use);
-    assert_eq!(res.tcp_orphaned, sockstats.tcp_orphaned);
-    assert_eq!(res.tcp_time_wait, sockstats.tcp_time_wait);
-    assert_eq!(res.udp_in_use, sockstats.udp_in_use);
-}
-
-fn proc_sockstat6_from_raw() -> io::Result<ProcNetSockStat6> {
-    let tcp_content = read_file("/proc/net/tcp6")?;
-    let tcp_in_use = tcp_content.lines().skip(1).count();
-
-    let udp_content = read_file("/proc/net/udp6")?;
-    let udp_in_use = udp_content.lines().skip(1).count();
-
-    Ok(ProcNetSockStat6 {
-        tcp_in_use,
-        udp_in_use,
-    })
+    assert_eq!(res.tcp_sockets_in_use, sockstats.tcp_in_use);
+    assert_eq!(res.tcp_sockets_time_wait, sockstats.tcp_time_wait);
+    assert_eq!(res.udp_sockets_in_use, sockstats.udp_in_use);
 }
 
 #[test]
 #[ignore]
 fn test_proc_sockstat6_raw() {
-    let res = proc_sockstat6_from_raw().unwrap();
+    let res = socket_stats_from(&sockets(Path::new("/proc/net")).unwrap());
     let sockstats6 = read_file("/proc/net/sockstat6")
         .and_then(|data| {
             proc_net_sockstat6(&data).map(|(_, res)| res).map_err(|e| {
@@ -738,28 +2824,41 @@ fn test_proc_sockstat6_raw() {
             })
         })
         .unwrap();
-    assert_eq!(res.tcp_in_use, sockstats6.tcp_in_use);
-    assert_eq!(res.udp_in_use, sockstats6.udp_in_use);
+    assert_eq!(res.tcp6_sockets_in_use, sockstats6.tcp_in_use);
+    assert_eq!(res.udp6_sockets_in_use, sockstats6.udp_in_use);
 }
 
 impl Measurement for MeasurementImpl {
     fn new() -> Self {
-        MeasurementImpl
+        MeasurementImpl::with_root("/").with_socket_backend(SocketBackend::Auto)
+    }
+
+    fn cpu_time(&self) -> io::Result<CpuSnapshot> {
+        cpu_time(&self.root)
     }
 
     fn cpu_load(
         &self,
     ) -> std::io::Result<DelayedMeasurement<Vec<SystemCpuLoad>>> {
-        cpu_time().map(|times| {
+        let root = self.root.clone();
+        self.cpu_time().map(|start| {
             DelayedMeasurement::new(
-                Box::new(move || {
-                    cpu_time().map(|delay_times| {
-                        delay_times
-                            .iter()
-                            .zip(times.iter())
-                            .map(|(now, prev)| (*now - prev).into())
-                            .collect::<Vec<_>>()
-                    })
+                Box::new(move |_| {
+                    cpu_time(&root).map(|now| (now - &start).into())
+                }),
+                None,
+            )
+        })
+    }
+
+    fn cpu_load_aggregate(
+        &self,
+    ) -> io::Result<DelayedMeasurement<SystemCpuLoad>> {
+        let root = self.root.clone();
+        self.cpu_time().map(|start| {
+            DelayedMeasurement::new(
+                Box::new(move |_| {
+                    cpu_time(&root).map(|now| (now - &start).load())
                 }),
                 None,
             )
@@ -770,55 +2869,139 @@ impl Measurement for MeasurementImpl {
         &self,
         pid: u32,
     ) -> std::io::Result<DelayedMeasurement<f64>> {
-        let total_core = cpu_time().iter().len();
+        let root = self.root.clone();
+        let total_core = cpu_time(&root)?.times.len().max(1);
         let clock_ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) } as f64;
-        proc_cpu_time(pid).map(|(utime, stime)| {
+        proc_cpu_time(&root, pid).map(|(utime, stime)| {
             DelayedMeasurement::new(
-                Box::new(move || {
-                    proc_cpu_time(pid).map(|(delayed_utime, delayed_stime)| {
-                        log::debug!("before: {utime} {stime}");
-                        log::debug!("after: {delayed_utime} {delayed_stime}");
-
-                        let used_time = delayed_utime
-                            .saturating_sub(utime)
-                            .saturating_add(delayed_stime.saturating_sub(stime))
-                            as f64
-                            / clock_ticks;
-                        // default delay measure is 1 sec
-                        (used_time * 100.0f64) / total_core as f64
-                    })
+                Box::new(move |elapsed| {
+                    proc_cpu_time(&root, pid).map(
+                        |(delayed_utime, delayed_stime)| {
+                            log::debug!("before: {utime} {stime}");
+                            log::debug!(
+                                "after: {delayed_utime} {delayed_stime}"
+                            );
+
+                            let used_time = delayed_utime
+                                .saturating_sub(utime)
+                                .saturating_add(
+                                    delayed_stime.saturating_sub(stime),
+                                )
+                                as f64
+                                / clock_ticks;
+                            let elapsed = elapsed.as_secs_f64();
+                            if elapsed == 0.0 {
+                                return 0.0;
+                            }
+                            (used_time * 100.0f64) / elapsed / total_core as f64
+                        },
+                    )
                 }),
                 None,
             )
         })
     }
 
+    fn load_average(&self) -> io::Result<LoadAverage> {
+        read_file(self.path("/proc/loadavg"))
+            .and_then(|data| {
+                proc_loadavg(&data).map(|(_, res)| res).map_err(|err| {
+                    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+                })
+            })
+            .or_else(|err| {
+                // getloadavg describes the caller, not the tree below root
+                if self.root != Path::new("/") {
+                    return Err(err);
+                }
+                unix::load_average()
+            })
+    }
+
+    fn pressure(&self) -> io::Result<SystemPressure> {
+        system_pressure(|resource| self.path("/proc/pressure").join(resource))
+    }
+
+    fn cgroup_pressure<P: AsRef<path::Path>>(
+        &self,
+        cgroup: P,
+    ) -> io::Result<SystemPressure> {
+        let cgroup = self.path(cgroup);
+        system_pressure(|resource| cgroup.join(format!("{resource}.pressure")))
+    }
+
     fn memory(&self) -> std::io::Result<SystemMemory> {
-        PlatformMemory::new().map(PlatformMemory::to_memory)
+        PlatformMemory::new(&self.root).map(PlatformMemory::into_memory)
     }
 
     fn memory_by_pid(&self, pid: u32) -> std::io::Result<(u64, u64)> {
-        let status = proc_status(pid)?;
+        let status = proc_status(&self.root, pid)?;
         Ok((status.vm_rss, status.vm_size))
     }
 
+    fn vmstat(&self) -> io::Result<VmStat> {
+        vmstat(&self.root)
+    }
+
+    fn vmstat_rates(&self) -> io::Result<DelayedMeasurement<VmStatRates>> {
+        let root = self.root.clone();
+        vmstat(&root).map(|start| {
+            DelayedMeasurement::new(
+                Box::new(move |elapsed| {
+                    vmstat(&root).map(|now| now.rates_since(&start, elapsed))
+                }),
+                None,
+            )
+        })
+    }
+
     fn swap(&self) -> std::io::Result<SystemSwap> {
-        PlatformMemory::new().map(PlatformMemory::to_swap)
+        PlatformMemory::new(&self.root).map(PlatformMemory::into_swap)
+    }
+
+    fn swap_devices(&self) -> io::Result<Vec<SwapDevice>> {
+        swap_devices(&self.root)
+    }
+
+    fn hugepages(&self) -> io::Result<HugePages> {
+        let meminfo = memory_stats(&self.root)?;
+        Ok(HugePages {
+            pools: hugepage_pools(&self.root)?,
+            transparent: transparent_hugepages(&self.root, &meminfo),
+        })
+    }
+
+    fn slab_info(&self) -> io::Result<SlabInfo> {
+        slab_info(&self.root)
+    }
+
+    fn numa_nodes(&self) -> io::Result<Vec<NumaNode>> {
+        let mut nodes = Vec::new();
+        for entry in std::fs::read_dir(self.path("/sys/devices/system/node"))? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(Ok(id)) =
+                name.strip_prefix("node").map(|id| id.parse::<usize>())
+            {
+                nodes.push(numa_node(id, &entry.path())?);
+            }
+        }
+        nodes.sort_by_key(|node| node.id);
+        Ok(nodes)
     }
 
     fn mounts(&self) -> io::Result<Vec<FileSystem>> {
-        read_file("/proc/mounts")
+        read_file(self.path("/proc/mounts"))
             .and_then(|data| {
                 proc_mounts(&data).map(|(_, mounts)| mounts).map_err(|e| {
                     io::Error::new(io::ErrorKind::InvalidData, e.to_string())
                 })
             })
-            .and_then(|mounts| {
-                let res = mounts
+            .map(|mounts| {
+                mounts
                     .into_iter()
-                    .filter_map(|mount| stat_mount(mount).ok())
-                    .collect::<Vec<_>>();
-                Ok(res)
+                    .filter_map(|mount| stat_mount(&self.root, mount).ok())
+                    .collect::<Vec<_>>()
             })
     }
 
@@ -826,7 +3009,7 @@ impl Measurement for MeasurementImpl {
         &self,
         path: P,
     ) -> io::Result<FileSystem> {
-        read_file("/proc/mounts")
+        read_file(self.path("/proc/mounts"))
             .and_then(|data| {
                 proc_mounts(&data).map(|(_, res)| res).map_err(|err| {
                     io::Error::new(io::ErrorKind::InvalidData, err.to_string())
@@ -842,53 +3025,141 @@ impl Measurement for MeasurementImpl {
                         io::Error::new(io::ErrorKind::NotFound, "No such mount")
                     })
             })
-            .and_then(stat_mount)
+            .and_then(|mount| stat_mount(&self.root, mount))
+    }
+
+    fn block_device_stats(
+        &self,
+    ) -> io::Result<BTreeMap<String, BlockDeviceStats>> {
+        block_device_stats(&self.root)
+    }
+
+    fn block_device_load(
+        &self,
+    ) -> io::Result<DelayedMeasurement<BTreeMap<String, BlockDeviceLoad>>> {
+        let root = self.root.clone();
+        block_device_stats(&root).map(|stats| {
+            DelayedMeasurement::new(
+                Box::new(move |elapsed| {
+                    block_device_stats(&root).map(|delayed_stats| {
+                        delayed_stats
+                            .iter()
+                            .filter_map(|(name, now)| {
+                                stats.get(name).map(|prev| {
+                                    (
+                                        name.clone(),
+                                        now.load_since(prev, elapsed),
+                                    )
+                                })
+                            })
+                            .collect::<BTreeMap<_, _>>()
+                    })
+                }),
+                None,
+            )
+        })
     }
 
     fn networks(&self) -> io::Result<BTreeMap<String, Network>> {
         unix::networks()
     }
 
+    fn network_links(&self) -> io::Result<BTreeMap<String, NetworkLink>> {
+        let mut links = BTreeMap::new();
+        for name in sys_class_net(&self.root)? {
+            let link = sys_network_link(&self.root, &name)?;
+            links.insert(name, link);
+        }
+        Ok(links)
+    }
+
     fn network_stats(&self, interface: &str) -> io::Result<NetworkStats> {
-        let path_root: String =
-            ("/sys/class/net/".to_string() + interface) + "/statistics/";
-        let stats_file = |file: &str| (&path_root).to_string() + file;
-
-        let rx_bytes: u64 = value_from_file::<u64>(&stats_file("rx_bytes"))?;
-        let tx_bytes: u64 = value_from_file::<u64>(&stats_file("tx_bytes"))?;
-        let rx_packets: u64 =
-            value_from_file::<u64>(&stats_file("rx_packets"))?;
-        let tx_packets: u64 =
-            value_from_file::<u64>(&stats_file("tx_packets"))?;
-        let rx_errors: u64 = value_from_file::<u64>(&stats_file("rx_errors"))?;
-        let tx_errors: u64 = value_from_file::<u64>(&stats_file("tx_errors"))?;
-
-        Ok(NetworkStats {
-            rx_bytes: ByteSize::b(rx_bytes),
-            tx_bytes: ByteSize::b(tx_bytes),
-            rx_packets,
-            tx_packets,
-            rx_errors,
-            tx_errors,
+        proc_net_dev_stats(&self.root)
+            .ok()
+            .and_then(|mut stats| stats.remove(interface))
+            .map_or_else(|| sys_network_stats(&self.root, interface), Ok)
+    }
+
+    fn all_network_stats(&self) -> io::Result<BTreeMap<String, NetworkStats>> {
+        all_network_stats(&self.root)
+    }
+
+    fn network_rates(
+        &self,
+    ) -> io::Result<DelayedMeasurement<BTreeMap<String, NetworkRates>>> {
+        let root = self.root.clone();
+        all_network_stats(&root).map(|stats| {
+            DelayedMeasurement::new(
+                Box::new(move |elapsed| {
+                    all_network_stats(&root).map(|delayed_stats| {
+                        delayed_stats
+                            .iter()
+                            .filter_map(|(name, now)| {
+                                stats.get(name).map(|prev| {
+                                    (
+                                        name.clone(),
+                                        now.rates_since(prev, elapsed),
+                                    )
+                                })
+                            })
+                            .collect::<BTreeMap<_, _>>()
+                    })
+                }),
+                None,
+            )
         })
     }
 
     fn socket_stats(&self) -> io::Result<SocketStats> {
-        let sockstats = proc_sockstat_from_raw()?;
-        let sockstats6 = proc_sockstat6_from_raw()?;
-
-        Ok(SocketStats {
-            tcp_sockets_in_use: sockstats.tcp_in_use,
-            tcp_sockets_orphan: sockstats.tcp_orphaned,
-            tcp_sockets_time_wait: sockstats.tcp_time_wait,
-            udp_sockets_in_use: sockstats.udp_in_use,
-            tcp6_sockets_in_use: sockstats6.tcp_in_use,
-            udp6_sockets_in_use: sockstats6.udp_in_use,
+        let stats = SocketStats {
+            tcp_sockets_orphan: proc_sockstat(&self.root)?.tcp_orphaned,
+            ..socket_stats_from(&host_sockets(
+                &self.root, self.socket_backend, false,
+            )?)
+        };
+        with_other_sockets(&self.root, stats)
+    }
+
+    fn unix_sockets(&self) -> io::Result<Vec<UnixSocket>> {
+        unix_sockets(&self.root)
+    }
+
+    fn protocol_counters(&self) -> io::Result<ProtocolCounters> {
+        protocol_counters(&self.root)
+    }
+
+    fn protocol_rates(&self) -> io::Result<DelayedMeasurement<ProtocolRates>> {
+        let root = self.root.clone();
+        protocol_counters(&root).map(|start| {
+            DelayedMeasurement::new(
+                Box::new(move |elapsed| {
+                    protocol_counters(&root)
+                        .map(|now| now.rates_since(&start, elapsed))
+                }),
+                None,
+            )
         })
     }
 
+    fn sockets(&self) -> io::Result<Vec<Socket>> {
+        host_sockets(&self.root, self.socket_backend, true)
+    }
+
+    fn listening_ports(&self) -> io::Result<Vec<ListeningPort>> {
+        listening_ports(&self.root, self.socket_backend)
+    }
+
+    fn sockets_by_pid(&self, pid: u32) -> io::Result<Vec<Socket>> {
+        let fds = socket_fds(&self.root, pid)?;
+        // The process may live in another network namespace
+        Ok(sockets(&self.path(format!("/proc/{pid}/net")))?
+            .into_iter()
+            .filter(|socket| fds.contains_key(&socket.inode))
+            .collect())
+    }
+
     fn boot_time(&self) -> io::Result<time::OffsetDateTime> {
-        read_file("/proc/stat").and_then(|data| {
+        read_file(self.path("/proc/stat")).and_then(|data| {
             data.lines()
                 .find(|line| line.starts_with("btime "))
                 .ok_or(io::Error::new(
@@ -922,7 +3193,7 @@ impl Measurement for MeasurementImpl {
     }
 
     fn process_uptime(&self, pid: u32) -> io::Result<std::time::Duration> {
-        let uptime_content = read_file("/proc/uptime")?;
+        let uptime_content = read_file(self.path("/proc/uptime"))?;
         let system_uptime_secs: f64 = uptime_content
             .split_whitespace()
             .next()
@@ -933,7 +3204,7 @@ impl Measurement for MeasurementImpl {
             .parse()
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
 
-        let process_stat = read_file(format!("/proc/{}/stat", pid).as_str())?;
+        let process_stat = read_file(self.path(format!("/proc/{}/stat", pid)))?;
         let parts: Vec<&str> = process_stat.split_whitespace().collect();
         let start_time_ticks: u64 = parts
             .get(21)
@@ -955,11 +3226,11 @@ impl Measurement for MeasurementImpl {
 
     fn process_pid(&self, cmd: &str) -> io::Result<Vec<usize>> {
         let mut pids = Vec::new();
-        for entry in std::fs::read_dir("/proc")? {
+        for entry in std::fs::read_dir(self.path("/proc"))? {
             let entry = entry?;
             let pid_str = entry.file_name().to_string_lossy().to_string();
             if let Ok(pid) = pid_str.parse::<usize>() {
-                let cmd_path = format!("/proc/{}/cmdline", pid);
+                let cmd_path = self.path(format!("/proc/{}/cmdline", pid));
                 if let Ok(cmdline) = read_file(&cmd_path) {
                     if cmdline.contains(cmd) {
                         pids.push(pid);
@@ -972,14 +3243,18 @@ impl Measurement for MeasurementImpl {
     }
 
     fn process_status(&self, pid: u32) -> io::Result<ProcessInfo> {
-        proc_status(pid)
+        proc_status(&self.root, pid)
     }
 }
 
 impl PlatformMemory {
-    fn new() -> io::Result<Self> {
-        memory_stats()
-            .or_else(|_| {
+    fn new(root: &Path) -> io::Result<Self> {
+        memory_stats(root)
+            .or_else(|err| {
+                // sysinfo describes the caller, not the tree below `root`
+                if root != Path::new("/") {
+                    return Err(err);
+                }
                 let mut meminfo = BTreeMap::new();
                 let mut info: sysinfo = unsafe { mem::zeroed() };
                 unsafe { sysinfo(&mut info) };
@@ -1000,6 +3275,15 @@ impl PlatformMemory {
                     "Buffers".to_owned(),
                     ByteSize::b(info.bufferram as u64 * unit),
                 );
+                // sysinfo knows nothing about the page cache, so the buffers
+                // are the only memory we can count as reclaimable. This is a
+                // lower bound of what MemAvailable would report
+                meminfo.insert(
+                    "MemAvailable".to_owned(),
+                    ByteSize::b(
+                        (info.freeram as u64 + info.bufferram as u64) * unit,
+                    ),
+                );
                 meminfo.insert(
                     "SwapTotal".to_owned(),
                     ByteSize::b(info.totalswap as u64 * unit),
@@ -1013,23 +3297,38 @@ impl PlatformMemory {
             .map(|meminfo| PlatformMemory { meminfo })
     }
 
-    fn to_memory(self) -> SystemMemory {
+    fn into_memory(self) -> SystemMemory {
         let meminfo = &self.meminfo;
+        let get =
+            |key: &str| meminfo.get(key).copied().unwrap_or(ByteSize::b(0));
+        let total = get("MemTotal");
+        let free = get("MemFree");
+        let cached = get("Cached");
+        let buffers = get("Buffers");
+        let shared = get("Shmem");
+        let slab_reclaimable = get("SReclaimable");
+        let available =
+            meminfo.get("MemAvailable").copied().unwrap_or_else(|| {
+                saturating_sub_bytes(
+                    free + buffers + cached + slab_reclaimable,
+                    shared,
+                )
+            });
         SystemMemory {
-            total: meminfo.get("MemTotal").copied().unwrap_or(ByteSize::b(0)),
-            free: meminfo.get("MemFree").copied().unwrap_or(ByteSize::b(0)),
-            used: saturating_sub_bytes(
-                meminfo.get("MemTotal").copied().unwrap_or(ByteSize::b(0)),
-                meminfo.get("MemFree").copied().unwrap_or(ByteSize::b(0))
-                    + meminfo.get("Buffers").copied().unwrap_or(ByteSize::b(0))
-                    + meminfo.get("Cached").copied().unwrap_or(ByteSize::b(0)),
-            ),
+            total,
+            free,
+            available,
+            used: saturating_sub_bytes(total, available),
+            cached,
+            buffers,
+            shared,
+            slab_reclaimable,
             platform: self,
         }
     }
 
     // Convert the platform memory information to Swap
-    fn to_swap(self) -> SystemSwap {
+    fn into_swap(self) -> SystemSwap {
         let meminfo = &self.meminfo;
         SystemSwap {
             total: meminfo.get("SwapTotal").copied().unwrap_or(ByteSize::b(0)),
@@ -1038,3 +3337,112 @@ impl PlatformMemory {
         }
     }
 }
+
+#[test]
+fn test_with_root() {
+    let fixture = Fixture::new(
+        "root",
+        [
+            (
+                "proc/stat",
+                "cpu  200 0 100 700 0 0 0 0 0 0
+cpu0 100 0 50 350 0 0 0 0 0 0
+cpu1 100 0 50 350 0 0 0 0 0 0
+btime 1738426511
+",
+            ),
+            (
+                "proc/meminfo",
+                "MemTotal:       2048 kB\nMemFree:        1024 kB\n",
+            ),
+            ("proc/diskstats", "   8       0 sda 1 0 8 1 0 0 0 0 0 1 1\n"),
+            ("proc/42/cmdline", "fixture-daemon\0--flag"),
+        ],
+    );
+
+    let measurement = MeasurementImpl::with_root(fixture.root());
+    let memory = measurement.memory().unwrap();
+    assert_eq!(memory.total, ByteSize::kib(2048));
+    assert_eq!(memory.free, ByteSize::kib(1024));
+    assert_eq!(
+        measurement.boot_time().unwrap().unix_timestamp(),
+        1738426511
+    );
+    assert!(measurement
+        .block_device_stats()
+        .unwrap()
+        .contains_key("sda"));
+    assert_eq!(measurement.process_pid("fixture-daemon").unwrap(), vec![42]);
+
+    let bare = Fixture::empty("bare-root");
+    let measurement = MeasurementImpl::with_root(bare.root());
+    assert!(measurement.memory().is_err());
+    assert!(measurement.swap().is_err());
+    assert!(measurement.load_average().is_err());
+}
+
+#[test]
+fn test_cpu_load_by_pid() {
+    let stat = |utime: u64| {
+        format!("42 (worker) R 1 42 42 0 -1 4194304 0 0 0 0 {utime} 0 0 0 20 0 1 0 100 0 0")
+    };
+    let fixture = Fixture::new(
+        "cpu-load-pid",
+        [
+            (
+                "proc/stat",
+                "cpu  400 0 0 400 0 0 0 0 0 0
+cpu0 100 0 0 100 0 0 0 0 0 0
+cpu1 100 0 0 100 0 0 0 0 0 0
+cpu2 100 0 0 100 0 0 0 0 0 0
+cpu3 100 0 0 100 0 0 0 0 0 0
+"
+                .to_string(),
+            ),
+            ("proc/42/stat", stat(100)),
+        ],
+    );
+
+    let measurement = MeasurementImpl::with_root(fixture.root());
+    let load = measurement.cpu_load_by_pid(42).unwrap();
+    // four CPU seconds over the one second delay, on four cores
+    fixture.write("proc/42/stat", stat(100 + 4 * clock_ticks() as u64));
+    let load = load.done().unwrap();
+    assert!((90.0..=100.0).contains(&load), "{load}");
+}
+
+#[test]
+fn test_platform_memory_into_memory() {
+    let meminfo = proc_meminfo(
+        "MemTotal:       32345596 kB
+MemFree:        13160208 kB
+MemAvailable:   27792164 kB
+Buffers:            4724 kB
+Cached:         14776312 kB
+Shmem:            134716 kB
+SReclaimable:     427080 kB
+",
+    )
+    .unwrap()
+    .1;
+    let memory = PlatformMemory {
+        meminfo: meminfo.clone(),
+    }
+    .into_memory();
+    assert_eq!(memory.available, ByteSize::kib(27792164));
+    assert_eq!(memory.used, ByteSize::kib(32345596 - 27792164));
+    assert_eq!(memory.cached, ByteSize::kib(14776312));
+    assert_eq!(memory.shared, ByteSize::kib(134716));
+    assert_eq!(memory.slab_reclaimable, ByteSize::kib(427080));
+
+    let mut old_kernel = meminfo;
+    old_kernel.remove("MemAvailable");
+    let memory = PlatformMemory {
+        meminfo: old_kernel,
+    }
+    .into_memory();
+    assert_eq!(
+        memory.available,
+        ByteSize::kib(13160208 + 4724 + 14776312 + 427080 - 134716)
+    );
+}
@@ -4,15 +4,18 @@ use bytesize::ByteSize;
 use libc::{
     c_int, host_processor_info, host_statistics64, mach_host_self,
     mach_msg_type_number_t, natural_t, processor_cpu_load_info_data_t, sysconf,
-    vm_statistics64, HOST_VM_INFO64, KERN_SUCCESS, PROCESSOR_CPU_LOAD_INFO,
-    _SC_PHYS_PAGES,
+    vm_statistics64, _SC_PHYS_PAGES, HOST_VM_INFO64, KERN_SUCCESS,
+    PROCESSOR_CPU_LOAD_INFO,
 };
 use mach2::traps::mach_task_self;
 
 use crate::{
     data::SystemCpuLoad,
-    disk::FileSystem,
-    network::{Network, NetworkStats, SocketStats},
+    disk::{BlockDeviceLoad, BlockDeviceStats, FileSystem},
+    network::{
+        ListeningPort, Network, NetworkLink, NetworkRates, NetworkStats,
+        ProtocolCounters, ProtocolRates, Socket, SocketStats, UnixSocket,
+    },
     platform::unix,
     process::ProcessInfo,
     PlatformMemory, PlatformSwap, SystemMemory, SystemSwap,
@@ -81,13 +84,17 @@ impl Measurement for MeasurementImpl {
         MeasurementImpl
     }
 
+    fn cpu_time(&self) -> io::Result<crate::CpuSnapshot> {
+        Err(io::Error::other("Not supported"))
+    }
+
     fn cpu_load(
         &self,
     ) -> std::io::Result<
         crate::data::DelayedMeasurement<Vec<crate::data::SystemCpuLoad>>,
     > {
         Ok(crate::data::DelayedMeasurement::new(
-            Box::new(fetch_cpu_load),
+            Box::new(|_| fetch_cpu_load()),
             None,
         ))
     }
@@ -99,6 +106,21 @@ impl Measurement for MeasurementImpl {
         Err(io::Error::new(io::ErrorKind::Other, "Not supported"))
     }
 
+    fn load_average(&self) -> io::Result<crate::LoadAverage> {
+        unix::load_average()
+    }
+
+    fn pressure(&self) -> io::Result<crate::SystemPressure> {
+        Err(io::Error::other("Not supported"))
+    }
+
+    fn cgroup_pressure<P: AsRef<std::path::Path>>(
+        &self,
+        _cgroup: P,
+    ) -> io::Result<crate::SystemPressure> {
+        Err(io::Error::other("Not supported"))
+    }
+
     fn memory_by_pid(&self, _pid: u32) -> std::io::Result<(u64, u64)> {
         Err(io::Error::new(io::ErrorKind::Other, "Not supported"))
     }
@@ -144,7 +166,14 @@ impl Measurement for MeasurementImpl {
                 Ok(SystemMemory {
                     total: pmem.total,
                     free: pmem.free,
+                    available: pmem.free + pmem.inactive,
                     used: pmem.active + pmem.wired,
+                    cached: ByteSize::b(
+                        vm_stats.external_page_count as u64 * page_size,
+                    ),
+                    buffers: ByteSize::b(0),
+                    shared: ByteSize::b(0),
+                    slab_reclaimable: ByteSize::b(0),
                     platform: pmem,
                 })
             }
@@ -152,6 +181,16 @@ impl Measurement for MeasurementImpl {
         }
     }
 
+    fn vmstat(&self) -> io::Result<crate::VmStat> {
+        Err(io::Error::other("Not supported"))
+    }
+
+    fn vmstat_rates(
+        &self,
+    ) -> io::Result<crate::DelayedMeasurement<crate::VmStatRates>> {
+        Err(io::Error::other("Not supported"))
+    }
+
     fn swap(&self) -> std::io::Result<crate::SystemSwap> {
         let mut vm_stats: vm_statistics64 = unsafe { mem::zeroed() };
         let mut count = mem::size_of::<vm_statistics64>() as u32
@@ -186,14 +225,71 @@ impl Measurement for MeasurementImpl {
         }
     }
 
+    fn swap_devices(&self) -> io::Result<Vec<crate::SwapDevice>> {
+        Err(io::Error::other("Not supported"))
+    }
+
+    fn hugepages(&self) -> io::Result<crate::HugePages> {
+        Err(io::Error::other("Not supported"))
+    }
+
+    fn slab_info(&self) -> io::Result<crate::SlabInfo> {
+        Err(io::Error::other("Not supported"))
+    }
+
+    fn numa_nodes(&self) -> io::Result<Vec<crate::numa::NumaNode>> {
+        Err(io::Error::other("Not supported"))
+    }
+
     fn networks(&self) -> io::Result<BTreeMap<String, Network>> {
         unix::networks()
     }
 
+    fn network_links(&self) -> io::Result<BTreeMap<String, NetworkLink>> {
+        Err(io::Error::other("Not supported"))
+    }
+
     fn network_stats(&self, interface: &str) -> io::Result<NetworkStats> {
         Err(io::Error::new(io::ErrorKind::Other, "Not supported"))
     }
 
+    fn all_network_stats(&self) -> io::Result<BTreeMap<String, NetworkStats>> {
+        Err(io::Error::other("Not supported"))
+    }
+
+    fn network_rates(
+        &self,
+    ) -> io::Result<crate::DelayedMeasurement<BTreeMap<String, NetworkRates>>>
+    {
+        Err(io::Error::other("Not supported"))
+    }
+
+    fn protocol_counters(&self) -> io::Result<ProtocolCounters> {
+        Err(io::Error::other("Not supported"))
+    }
+
+    fn protocol_rates(
+        &self,
+    ) -> io::Result<crate::DelayedMeasurement<ProtocolRates>> {
+        Err(io::Error::other("Not supported"))
+    }
+
+    fn sockets(&self) -> io::Result<Vec<Socket>> {
+        Err(io::Error::other("Not supported"))
+    }
+
+    fn unix_sockets(&self) -> io::Result<Vec<UnixSocket>> {
+        Err(io::Error::other("Not supported"))
+    }
+
+    fn listening_ports(&self) -> io::Result<Vec<ListeningPort>> {
+        Err(io::Error::other("Not supported"))
+    }
+
+    fn sockets_by_pid(&self, _: u32) -> io::Result<Vec<Socket>> {
+        Err(io::Error::other("Not supported"))
+    }
+
     fn boot_time(&self) -> io::Result<time::OffsetDateTime> {
         Err(io::Error::new(io::ErrorKind::Other, "Not supported"))
     }
@@ -206,6 +302,19 @@ impl Measurement for MeasurementImpl {
         Err(io::Error::new(io::ErrorKind::Other, "Not supported"))
     }
 
+    fn block_device_stats(
+        &self,
+    ) -> io::Result<BTreeMap<String, BlockDeviceStats>> {
+        Err(io::Error::other("Not supported"))
+    }
+
+    fn block_device_load(
+        &self,
+    ) -> io::Result<crate::DelayedMeasurement<BTreeMap<String, BlockDeviceLoad>>>
+    {
+        Err(io::Error::other("Not supported"))
+    }
+
     fn socket_stats(&self) -> io::Result<SocketStats> {
         Err(io::Error::new(io::ErrorKind::Other, "Not supported"))
     }
@@ -8,7 +8,9 @@ pub use self::macos::MeasurementImpl;
 #[cfg(target_os = "linux")]
 pub mod linux;
 #[cfg(target_os = "linux")]
-pub use self::linux::MeasurementImpl;
+pub use self::linux::{MeasurementImpl, SocketBackend};
+#[cfg(target_os = "linux")]
+mod sock_diag;
 
 #[cfg(unix)]
 pub mod unix;
@@ -5,17 +5,20 @@ use std::{
     ptr,
 };
 
-use libc::{freeifaddrs, getifaddrs, ifaddrs, sockaddr, AF_INET, AF_INET6};
+use libc::{
+    freeifaddrs, getifaddrs, ifaddrs, sockaddr, sockaddr_in, sockaddr_in6,
+    AF_INET, AF_INET6,
+};
 
-use crate::network::{IpAddr, Network, NetworkAddr};
+use crate::{
+    network::{InterfaceFlags, IpAddr, Network, NetworkAddr},
+    LoadAverage,
+};
 
 pub fn networks() -> io::Result<BTreeMap<String, Network>> {
     let mut ifap: *mut ifaddrs = ptr::null_mut();
     if unsafe { getifaddrs(&mut ifap) } != 0 {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "getifaddrs() failed",
-        ));
+        return Err(io::Error::other("getifaddrs() failed"));
     }
 
     let ifirst = ifap;
@@ -28,15 +31,31 @@ pub fn networks() -> io::Result<BTreeMap<String, Network>> {
                 .to_string_lossy()
                 .into_owned()
         };
+        let flags = InterfaceFlags(ifa.ifa_flags);
         let entry = result.entry(name.clone()).or_insert(Network {
             name,
             addrs: Vec::new(),
+            flags,
         });
         let addr = parse_addr(ifa.ifa_addr);
         if addr != IpAddr::Unsupported {
+            let netmask = parse_addr(ifa.ifa_netmask);
+            // The same field holds the broadcast or the peer address
+            let other = parse_addr(ifa_dstaddr(&ifa));
+            let (broadcast, peer) = if flags.is_point_to_point() {
+                (IpAddr::Empty, other)
+            } else if flags.is_broadcast() {
+                (other, IpAddr::Empty)
+            } else {
+                (IpAddr::Empty, IpAddr::Empty)
+            };
             entry.addrs.push(NetworkAddr {
+                prefix_len: netmask.prefix_len(),
+                scope_id: parse_scope_id(ifa.ifa_addr),
                 addr,
-                netmask: parse_addr(ifa.ifa_netmask),
+                netmask,
+                broadcast,
+                peer,
             });
         }
         ifap = unsafe { (*ifap).ifa_next };
@@ -46,23 +65,96 @@ pub fn networks() -> io::Result<BTreeMap<String, Network>> {
     Ok(result)
 }
 
+#[cfg(target_os = "linux")]
+fn ifa_dstaddr(ifa: &ifaddrs) -> *const sockaddr {
+    ifa.ifa_ifu
+}
+
+#[cfg(target_os = "macos")]
+fn ifa_dstaddr(ifa: &ifaddrs) -> *const sockaddr {
+    ifa.ifa_dstaddr
+}
+
 fn parse_addr(aptr: *const sockaddr) -> IpAddr {
     if aptr.is_null() {
         return IpAddr::Empty;
     }
 
-    let addr = unsafe { *aptr };
-    match addr.sa_family as i32 {
-        AF_INET => IpAddr::V4(Ipv4Addr::new(
-            addr.sa_data[2] as u8,
-            addr.sa_data[3] as u8,
-            addr.sa_data[4] as u8,
-            addr.sa_data[5] as u8,
-        )),
+    match unsafe { (*aptr).sa_family } as i32 {
+        AF_INET => {
+            let sin = unsafe { &*(aptr as *const sockaddr_in) };
+            IpAddr::V4(Ipv4Addr::from(u32::from_be(sin.sin_addr.s_addr)))
+        }
         AF_INET6 => {
-            let sin6 = unsafe { &*(aptr as *const libc::sockaddr_in6) };
+            let sin6 = unsafe { &*(aptr as *const sockaddr_in6) };
             IpAddr::V6(Ipv6Addr::from(sin6.sin6_addr.s6_addr))
         }
+        #[cfg(target_os = "linux")]
+        libc::AF_PACKET => {
+            let sll = unsafe { &*(aptr as *const libc::sockaddr_ll) };
+            let len = (sll.sll_halen as usize).min(sll.sll_addr.len());
+            IpAddr::Link(sll.sll_addr[..len].to_vec())
+        }
+        #[cfg(target_os = "macos")]
+        libc::AF_LINK => {
+            let sdl = unsafe { &*(aptr as *const libc::sockaddr_dl) };
+            // The address follows the interface name and may run past the
+            // declared size of `sdl_data`, so read it through `aptr`, which
+            // is valid for the `sdl_len` bytes of the whole sockaddr
+            let len = sdl.sdl_len as usize;
+            let start = (std::mem::offset_of!(libc::sockaddr_dl, sdl_data)
+                + sdl.sdl_nlen as usize)
+                .min(len);
+            let end = (start + sdl.sdl_alen as usize).min(len);
+            let addr = unsafe {
+                std::slice::from_raw_parts(
+                    (aptr as *const u8).add(start),
+                    end.saturating_sub(start),
+                )
+            };
+            IpAddr::Link(addr.to_vec())
+        }
         _ => IpAddr::Unsupported,
     }
 }
+
+fn parse_scope_id(aptr: *const sockaddr) -> Option<u32> {
+    if aptr.is_null() || unsafe { (*aptr).sa_family } as i32 != AF_INET6 {
+        return None;
+    }
+    let sin6 = unsafe { &*(aptr as *const sockaddr_in6) };
+    Some(sin6.sin6_scope_id)
+}
+
+#[test]
+fn test_parse_addr() {
+    let mut sin: sockaddr_in = unsafe { std::mem::zeroed() };
+    sin.sin_family = AF_INET as libc::sa_family_t;
+    sin.sin_addr.s_addr = u32::from(Ipv4Addr::new(192, 168, 1, 20)).to_be();
+    let addr = parse_addr(&sin as *const sockaddr_in as *const sockaddr);
+    assert_eq!(addr, IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)));
+
+    let mut sin6: sockaddr_in6 = unsafe { std::mem::zeroed() };
+    sin6.sin6_family = AF_INET6 as libc::sa_family_t;
+    sin6.sin6_addr.s6_addr = "fe80::1".parse::<Ipv6Addr>().unwrap().octets();
+    sin6.sin6_scope_id = 2;
+    let aptr = &sin6 as *const sockaddr_in6 as *const sockaddr;
+    assert_eq!(parse_addr(aptr), IpAddr::V6("fe80::1".parse().unwrap()));
+    assert_eq!(parse_scope_id(aptr), Some(2));
+    assert_eq!(parse_scope_id(ptr::null()), None);
+    assert_eq!(parse_addr(ptr::null()), IpAddr::Empty);
+}
+
+pub fn load_average() -> io::Result<LoadAverage> {
+    let mut loads = [0f64; 3];
+    if unsafe { libc::getloadavg(loads.as_mut_ptr(), 3) } != 3 {
+        return Err(io::Error::other("getloadavg() failed"));
+    }
+
+    Ok(LoadAverage {
+        one: loads[0],
+        five: loads[1],
+        fifteen: loads[2],
+        ..Default::default()
+    })
+}